@@ -0,0 +1,33 @@
+// Cost of constructing a `Client`, which defers building its
+// `reqwest::Client` to the first request, versus also building a
+// `reqwest::Client` up front as every constructor used to do.
+//
+//     cargo bench --bench client_construction
+
+use leap_connect::v1::api::Client;
+use std::time::{Duration, Instant};
+
+const ITERATIONS: u32 = 200;
+
+fn bench<T>(build: impl Fn() -> T) -> Duration {
+    build();
+    let start = Instant::now();
+    for _ in 0..ITERATIONS {
+        std::hint::black_box(build());
+    }
+    start.elapsed() / ITERATIONS
+}
+
+fn client() -> Client {
+    Client::builder()
+        .api_key("sk-bench".to_owned())
+        .build()
+        .unwrap()
+}
+
+fn main() {
+    let lazy = bench(client);
+    let eager = bench(|| (client(), reqwest::Client::new()));
+    println!("lazy http client:  {:?} per client", lazy);
+    println!("eager http client: {:?} per client", eager);
+}
@@ -0,0 +1,69 @@
+// Per-response cost of the default header capture (request id and rate-limit
+// headers) versus `HeaderCapture::All`, for a small response carrying the
+// couple dozen headers a typical API gateway adds. The response comes from a
+// loopback server, see `support`.
+//
+//     cargo bench --bench header_capture
+
+mod support;
+
+use leap_connect::v1::api::{Client, HeaderCapture};
+use std::time::{Duration, Instant};
+
+const ITERATIONS: u32 = 2_000;
+
+fn headers() -> Vec<(String, String)> {
+    let mut headers: Vec<(String, String)> = [
+        ("x-request-id", "req_0123456789abcdef"),
+        ("x-ratelimit-limit-requests", "10000"),
+        ("x-ratelimit-remaining-requests", "9999"),
+        ("x-ratelimit-reset-requests", "6ms"),
+        ("x-ratelimit-limit-tokens", "1000000"),
+        ("x-ratelimit-remaining-tokens", "999000"),
+        ("x-ratelimit-reset-tokens", "60ms"),
+        ("cache-control", "no-store"),
+        ("strict-transport-security", "max-age=31536000"),
+    ]
+    .iter()
+    .map(|(name, value)| (name.to_string(), value.to_string()))
+    .collect();
+    for i in 0..20 {
+        headers.push((
+            format!("x-gateway-trace-{}", i),
+            "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01".to_owned(),
+        ));
+    }
+    headers
+}
+
+fn bench(runtime: &tokio::runtime::Runtime, capture: HeaderCapture) -> (Duration, usize) {
+    let body = br#"{"object": "list", "data": []}"#;
+    let endpoint = support::serve(support::response(&headers(), body, true, body.len()));
+    let mut client = Client::new_with_endpoint(endpoint, "sk-bench".to_owned());
+    client.set_header_capture(capture);
+    // The warm-up run opens the connection and reports what is kept.
+    let captured = runtime
+        .block_on(client.file_list())
+        .unwrap()
+        .headers
+        .map_or(0, |headers| headers.len());
+    let start = Instant::now();
+    for _ in 0..ITERATIONS {
+        runtime.block_on(client.file_list()).unwrap();
+    }
+    (start.elapsed() / ITERATIONS, captured)
+}
+
+fn main() {
+    let runtime = tokio::runtime::Runtime::new().unwrap();
+    let (default, default_len) = bench(&runtime, HeaderCapture::default());
+    let (all, all_len) = bench(&runtime, HeaderCapture::All);
+    println!(
+        "default capture: {:?} per response, {} headers kept",
+        default, default_len
+    );
+    println!(
+        "capture all:     {:?} per response, {} headers kept",
+        all, all_len
+    );
+}
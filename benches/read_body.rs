@@ -0,0 +1,65 @@
+// Reading a large embedding batch with and without `Content-Length`, i.e. with
+// the body buffer reserved up front versus grown chunk by chunk. The response
+// comes from a loopback server, see `support`.
+//
+//     cargo bench --bench read_body
+
+mod support;
+
+use leap_connect::v1::api::Client;
+use leap_connect::v1::embedding::EmbeddingRequest;
+use std::time::{Duration, Instant};
+
+const EMBEDDINGS: usize = 500;
+const DIMENSIONS: usize = 768;
+const CHUNK_SIZE: usize = 16 * 1024;
+const ITERATIONS: u32 = 50;
+
+fn embedding_batch() -> Vec<u8> {
+    let data: Vec<serde_json::Value> = (0..EMBEDDINGS)
+        .map(|index| {
+            let embedding: Vec<f64> = (0..DIMENSIONS)
+                .map(|i| ((index * DIMENSIONS + i) as f64).sin())
+                .collect();
+            serde_json::json!({"object": "embedding", "embedding": embedding, "index": index})
+        })
+        .collect();
+    let body = serde_json::json!({
+        "object": "list",
+        "data": data,
+        "model": "nomic-embed-text",
+        "usage": {"prompt_tokens": EMBEDDINGS, "completion_tokens": 0, "total_tokens": EMBEDDINGS},
+    });
+    serde_json::to_vec(&body).unwrap()
+}
+
+fn bench(runtime: &tokio::runtime::Runtime, body: &[u8], known_length: bool) -> Duration {
+    let endpoint = support::serve(support::response(&[], body, known_length, CHUNK_SIZE));
+    let client = Client::new_with_endpoint(endpoint, "sk-bench".to_owned());
+    let request = || EmbeddingRequest::new("nomic-embed-text".to_owned(), "input".to_owned());
+    // One warm-up run so both variants start with an open connection and the
+    // same allocator state.
+    runtime.block_on(client.embedding(request())).unwrap();
+    let start = Instant::now();
+    for _ in 0..ITERATIONS {
+        let response = runtime.block_on(client.embedding(request())).unwrap();
+        assert_eq!(response.data.len(), EMBEDDINGS);
+    }
+    start.elapsed() / ITERATIONS
+}
+
+fn main() {
+    let runtime = tokio::runtime::Runtime::new().unwrap();
+    let body = embedding_batch();
+    println!(
+        "embedding batch: {} embeddings x {} dimensions, {:.1} MiB in {} KiB chunks",
+        EMBEDDINGS,
+        DIMENSIONS,
+        body.len() as f64 / (1024.0 * 1024.0),
+        CHUNK_SIZE / 1024
+    );
+    let grown = bench(&runtime, &body, false);
+    let reserved = bench(&runtime, &body, true);
+    println!("without Content-Length: {:?} per response", grown);
+    println!("with Content-Length:    {:?} per response", reserved);
+}
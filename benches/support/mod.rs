@@ -0,0 +1,78 @@
+// A loopback HTTP/1.1 server for the benchmarks, answering every request with
+// the same canned response over keep-alive connections.
+
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::sync::Arc;
+
+/// Starts serving `response` (a complete HTTP response, see `response`) on a
+/// free local port and returns the endpoint to point a client at.
+pub fn serve(response: Vec<u8>) -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    let response = Arc::new(response);
+    std::thread::spawn(move || {
+        for stream in listener.incoming().flatten() {
+            let response = response.clone();
+            std::thread::spawn(move || {
+                let _ = answer(stream, &response);
+            });
+        }
+    });
+    format!("http://{}/v1", addr)
+}
+
+/// A `200 OK` carrying `body` and `headers`. With `known_length` the body is
+/// sent with a `Content-Length`, otherwise chunked in pieces of `chunk_size`.
+pub fn response(
+    headers: &[(String, String)],
+    body: &[u8],
+    known_length: bool,
+    chunk_size: usize,
+) -> Vec<u8> {
+    let mut response = b"HTTP/1.1 200 OK\r\ncontent-type: application/json\r\n".to_vec();
+    for (name, value) in headers {
+        response.extend_from_slice(format!("{}: {}\r\n", name, value).as_bytes());
+    }
+    if known_length {
+        response.extend_from_slice(format!("content-length: {}\r\n\r\n", body.len()).as_bytes());
+        response.extend_from_slice(body);
+    } else {
+        response.extend_from_slice(b"transfer-encoding: chunked\r\n\r\n");
+        for chunk in body.chunks(chunk_size) {
+            response.extend_from_slice(format!("{:x}\r\n", chunk.len()).as_bytes());
+            response.extend_from_slice(chunk);
+            response.extend_from_slice(b"\r\n");
+        }
+        response.extend_from_slice(b"0\r\n\r\n");
+    }
+    response
+}
+
+// Reads requests off one connection, skipping their bodies, and writes
+// `response` for each until the client hangs up.
+fn answer(stream: TcpStream, response: &[u8]) -> std::io::Result<()> {
+    let mut reader = BufReader::new(stream.try_clone()?);
+    let mut writer = stream;
+    loop {
+        let mut content_length = 0;
+        loop {
+            let mut line = String::new();
+            if reader.read_line(&mut line)? == 0 {
+                return Ok(());
+            }
+            let line = line.trim_end();
+            if line.is_empty() {
+                break;
+            }
+            if let Some((name, value)) = line.split_once(':') {
+                if name.eq_ignore_ascii_case("content-length") {
+                    content_length = value.trim().parse().unwrap_or(0);
+                }
+            }
+        }
+        let mut body = vec![0; content_length];
+        reader.read_exact(&mut body)?;
+        writer.write_all(response)?;
+    }
+}
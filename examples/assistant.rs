@@ -2,14 +2,13 @@ use leap_connect::v1::api::Client;
 use leap_connect::v1::assistant::AssistantRequest;
 use leap_connect::v1::common::MISTRAL;
 use leap_connect::v1::message::{CreateMessageRequest, MessageRole};
-use leap_connect::v1::run::CreateRunRequest;
+use leap_connect::v1::run::{CreateRunRequest, RunStatus};
 use leap_connect::v1::thread::CreateThreadRequest;
 use std::collections::HashMap;
-use std::env;
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
-    let client = Client::new(env::var("TUPLELEAP_AI_API_KEY").unwrap().to_string());
+    let client = Client::from_env()?;
 
     let mut tools = HashMap::new();
     tools.insert("type".to_string(), "code_interpreter".to_string());
@@ -47,7 +46,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
             .retrieve_run(thread_result.id.clone(), run_result.id.clone())
             .await
             .unwrap();
-        if run_result.status == "completed" {
+        if run_result.status == RunStatus::Completed {
             break;
         } else {
             println!("waiting...");
@@ -56,7 +55,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     }
 
     let list_message_result = client
-        .list_messages(thread_result.id.clone())
+        .list_messages(thread_result.id.clone(), None, None, None, None, None)
         .await
         .unwrap();
     for data in list_message_result.data {
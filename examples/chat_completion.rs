@@ -1,8 +1,7 @@
 use futures::StreamExt;
 use leap_connect::v1::api::Client;
-use leap_connect::v1::chat_completion::{self, ChatCompletionRequest};
+use leap_connect::v1::chat_completion::{self, ChatCompletionRequest, Messages};
 use leap_connect::v1::common::MISTRAL;
-use std::env;
 
 /*
 Add the following in settings.json file to run in vscode env
@@ -17,21 +16,16 @@ Add the following in settings.json file to run in vscode env
 */
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
-    println!("{}", env::var("TUPLELEAP_AI_API_KEY").unwrap().to_string());
-    let client = Client::new(env::var("TUPLELEAP_AI_API_KEY").unwrap().to_string());
-    let req = ChatCompletionRequest::new(
-        MISTRAL.to_string(),
-        vec![chat_completion::ChatCompletionMessage {
-            role: chat_completion::MessageRole::user,
-            content: chat_completion::Content::Text(String::from("What is bitcoin?")),
-            name: None,
-        }],
-    );
+    let client = Client::from_env()?;
+    let req = ChatCompletionRequest::new(MISTRAL, Messages::new().user("What is bitcoin?").build());
 
     let result_stream = client.chat_completion_stream(req).await?;
-    let list: Vec<chat_completion::ChatChunkResponse> = result_stream.collect().await;
+    let list: Vec<Result<chat_completion::ChatChunkResponse, _>> = result_stream.collect().await;
     for resp in list {
-        for choice in resp.choices.iter() {
+        for choice in resp?.choices.iter() {
+            if let Some(role) = &choice.delta.role {
+                println!("{:?}:", role);
+            }
             let data = &choice.delta.content;
             if data.is_some() {
                 print!("{}", data.clone().unwrap())
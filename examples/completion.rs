@@ -1,12 +1,11 @@
 use leap_connect::v1::api::Client;
 use leap_connect::v1::completion::{self, CompletionRequest};
-use std::env;
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
-    let client = Client::new(env::var("TUPLELEAP_AI_API_KEY").unwrap().to_string());
+    let client = Client::from_env()?;
 
     let req = CompletionRequest::new(
-        completion::GPT3_TEXT_DAVINCI_003.to_string(),
+        completion::GPT3_TEXT_DAVINCI_003,
         String::from("What is Bitcoin?"),
     )
     .max_tokens(3000)
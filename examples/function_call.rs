@@ -1,9 +1,9 @@
 use leap_connect::v1::api::Client;
-use leap_connect::v1::chat_completion::{self, ChatCompletionRequest};
+use leap_connect::v1::chat_completion::{self, ChatCompletionRequest, Messages};
 use leap_connect::v1::common::MISTRAL;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
-use std::{env, vec};
+use std::vec;
 
 fn get_coin_price(coin: &str) -> f64 {
     let coin = coin.to_lowercase();
@@ -16,7 +16,7 @@ fn get_coin_price(coin: &str) -> f64 {
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
-    let client = Client::new(env::var("TUPLELEAP_AI_API_KEY").unwrap().to_string());
+    let client = Client::from_env()?;
 
     let mut properties = HashMap::new();
     properties.insert(
@@ -29,16 +29,13 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     );
 
     let req = ChatCompletionRequest::new(
-        MISTRAL.to_string(),
-        vec![chat_completion::ChatCompletionMessage {
-            role: chat_completion::MessageRole::user,
-            content: chat_completion::Content::Text(String::from("What is the price of Ethereum?")),
-            name: None,
-        }],
+        MISTRAL,
+        Messages::new()
+            .user("What is the price of Ethereum?")
+            .build(),
     )
-    .tools(vec![chat_completion::Tool {
-        r#type: chat_completion::ToolType::Function,
-        function: chat_completion::Function {
+    .tools(vec![chat_completion::Tool::new_function(
+        chat_completion::Function {
             name: String::from("get_coin_price"),
             description: Some(String::from("Get the price of a cryptocurrency")),
             parameters: chat_completion::FunctionParameters {
@@ -47,7 +44,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                 required: Some(vec![String::from("coin")]),
             },
         },
-    }])
+    )])
     .tool_choice(chat_completion::ToolChoiceType::Auto);
 
     // debug request json
@@ -92,6 +89,9 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         Some(chat_completion::FinishReason::null) => {
             println!("Null");
         }
+        Some(chat_completion::FinishReason::Unknown(ref reason)) => {
+            println!("Unknown finish reason: {}", reason);
+        }
     }
     Ok(())
 }
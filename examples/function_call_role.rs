@@ -1,9 +1,9 @@
 use leap_connect::v1::api::Client;
-use leap_connect::v1::chat_completion::{self, ChatCompletionRequest};
+use leap_connect::v1::chat_completion::{self, ChatCompletionRequest, Messages};
 use leap_connect::v1::common::MISTRAL;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
-use std::{env, vec};
+use std::vec;
 
 fn get_coin_price(coin: &str) -> f64 {
     let coin = coin.to_lowercase();
@@ -15,7 +15,7 @@ fn get_coin_price(coin: &str) -> f64 {
 }
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
-    let client = Client::new(env::var("TUPLELEAP_AI_API_KEY").unwrap().to_string());
+    let client = Client::from_env()?;
 
     let mut properties = HashMap::new();
     properties.insert(
@@ -28,16 +28,13 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     );
 
     let req = ChatCompletionRequest::new(
-        MISTRAL.to_string(),
-        vec![chat_completion::ChatCompletionMessage {
-            role: chat_completion::MessageRole::user,
-            content: chat_completion::Content::Text(String::from("What is the price of Ethereum?")),
-            name: None,
-        }],
+        MISTRAL,
+        Messages::new()
+            .user("What is the price of Ethereum?")
+            .build(),
     )
-    .tools(vec![chat_completion::Tool {
-        r#type: chat_completion::ToolType::Function,
-        function: chat_completion::Function {
+    .tools(vec![chat_completion::Tool::new_function(
+        chat_completion::Function {
             name: String::from("get_coin_price"),
             description: Some(String::from("Get the price of a cryptocurrency")),
             parameters: chat_completion::FunctionParameters {
@@ -46,7 +43,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                 required: Some(vec![String::from("coin")]),
             },
         },
-    }]);
+    )]);
 
     let result = client.chat_completion(req).await?;
 
@@ -79,25 +76,14 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                 println!("price: {}", price);
 
                 let req = ChatCompletionRequest::new(
-                    MISTRAL.to_string(),
-                    vec![
-                        chat_completion::ChatCompletionMessage {
-                            role: chat_completion::MessageRole::user,
-                            content: chat_completion::Content::Text(String::from(
-                                "What is the price of Ethereum?",
-                            )),
-                            name: None,
-                        },
-                        chat_completion::ChatCompletionMessage {
-                            role: chat_completion::MessageRole::function,
-                            content: chat_completion::Content::Text({
-                                let price = get_coin_price(&coin);
-                                format!("{{\"price\": {}}}", price)
-                            }),
-                            name: Some(String::from("get_coin_price")),
-                        },
-                    ],
+                    MISTRAL,
+                    Messages::new()
+                        .user("What is the price of Ethereum?")
+                        .assistant_tool_calls(tool_calls.clone())
+                        .function("get_coin_price", format!("{{\"price\": {}}}", price))
+                        .build(),
                 );
+                req.validate()?;
 
                 let result = client.chat_completion(req).await?;
                 println!("{:?}", result.choices[0].message.content);
@@ -109,6 +95,9 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         Some(chat_completion::FinishReason::null) => {
             println!("Null");
         }
+        Some(chat_completion::FinishReason::Unknown(ref reason)) => {
+            println!("Unknown finish reason: {}", reason);
+        }
     }
     Ok(())
 }
@@ -0,0 +1,56 @@
+use hmac::{Hmac, Mac};
+use leap_connect::v1::api::Client;
+use leap_connect::v1::common::TEXT_EMBEDDING_3_SMALL;
+use leap_connect::v1::embedding::EmbeddingRequest;
+use leap_connect::v1::error::{APIError, APIErrorKind};
+use reqwest::header::{HeaderMap, HeaderValue};
+use sha2::Sha256;
+use std::env;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+// Signs `timestamp\nMETHOD\npath\nbody` with HMAC-SHA256, the scheme many API
+// gateways expect, and sends it along with the timestamp.
+fn sign(secret: &[u8], method: &str, path: &str, body: &[u8]) -> Result<HeaderMap, APIError> {
+    let timestamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_err(|e| APIError::new(APIErrorKind::Other, e.to_string()))?
+        .as_secs()
+        .to_string();
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret)
+        .map_err(|e| APIError::new(APIErrorKind::Other, e.to_string()))?;
+    mac.update(format!("{}\n{}\n{}\n", timestamp, method, path).as_bytes());
+    mac.update(body);
+    let signature: String = mac
+        .finalize()
+        .into_bytes()
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect();
+
+    let mut headers = HeaderMap::new();
+    headers.insert(
+        "x-signature-timestamp",
+        HeaderValue::from_str(&timestamp).unwrap(),
+    );
+    headers.insert("x-signature", HeaderValue::from_str(&signature).unwrap());
+    Ok(headers)
+}
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let secret = env::var("GATEWAY_SECRET")
+        .map_err(|_| "GATEWAY_SECRET is not set")?
+        .into_bytes();
+    let mut client = Client::from_env()?;
+    client.set_request_signer(move |method: &str, path: &str, body: &[u8]| {
+        sign(&secret, method, path, body)
+    });
+
+    let req = EmbeddingRequest::new(TEXT_EMBEDDING_3_SMALL, "Hello, world".to_string());
+    let result = client.embedding(req).await?;
+    println!("{:?}", result.data.len());
+
+    Ok(())
+}
+
+// GATEWAY_SECRET=xxxx TUPLELEAP_AI_API_KEY=xxxx cargo run --example hmac_signing
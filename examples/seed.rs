@@ -0,0 +1,36 @@
+use leap_connect::v1::api::Client;
+use leap_connect::v1::chat_completion::{ChatCompletionRequest, Messages};
+use leap_connect::v1::common::MISTRAL;
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let client = Client::from_env()?;
+
+    // The same seed and parameters should give the same answer, as long as the
+    // server reports the same system_fingerprint for both calls.
+    let req = ChatCompletionRequest::new(
+        MISTRAL,
+        Messages::new().user("Name a random fruit.").build(),
+    )
+    .seed(42)
+    .temperature(1.0);
+
+    let first = client.chat_completion(req.clone()).await?;
+    let second = client.chat_completion(req).await?;
+    for result in [&first, &second] {
+        println!(
+            "{:?} {:?}",
+            result.system_fingerprint, result.choices[0].message.content
+        );
+    }
+    if first.system_fingerprint == second.system_fingerprint {
+        println!(
+            "same answer: {}",
+            first.choices[0].message.content == second.choices[0].message.content
+        );
+    }
+
+    Ok(())
+}
+
+// TUPLELEAP_AI_API_KEY=xxxx cargo run --package leap-connect --example seed
@@ -1,10 +1,9 @@
 use leap_connect::v1::api::Client;
 use leap_connect::v1::audio::{self, AudioSpeechRequest, TTS_1};
-use std::env;
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
-    let client = Client::new(env::var("TUPLELEAP_AI_API_KEY").unwrap().to_string());
+    let client = Client::from_env()?;
 
     let req = AudioSpeechRequest::new(
         TTS_1.to_string(),
@@ -1,33 +1,23 @@
 use leap_connect::v1::api::Client;
 use leap_connect::v1::chat_completion::{self, ChatCompletionRequest};
 use leap_connect::v1::common::MISTRAL;
-use std::env;
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
-    let client = Client::new(env::var("TUPLELEAP_AI_API_KEY").unwrap().to_string());
+    let client = Client::from_env()?;
 
     let req = ChatCompletionRequest::new(
-        MISTRAL.to_string(),
+        MISTRAL,
         vec![chat_completion::ChatCompletionMessage {
             role: chat_completion::MessageRole::user,
             content: chat_completion::Content::ImageUrl(vec![
-                chat_completion::ImageUrl {
-                    r#type: chat_completion::ContentType::text,
-                    text: Some(String::from("What’s in this image?")),
-                    image_url: None,
-                },
-                chat_completion::ImageUrl {
-                    r#type: chat_completion::ContentType::image_url,
-                    text: None,
-                    image_url: Some(chat_completion::ImageUrlType {
-                        url: String::from(
-                            "https://upload.wikimedia.org/wikipedia/commons/5/50/Bitcoin.png",
-                        ),
-                    }),
-                },
+                chat_completion::ImageUrl::text(String::from("What’s in this image?")),
+                chat_completion::ImageUrl::url(String::from(
+                    "https://upload.wikimedia.org/wikipedia/commons/5/50/Bitcoin.png",
+                )),
             ]),
             name: None,
+            tool_calls: None,
         }],
     );
 
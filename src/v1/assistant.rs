@@ -2,6 +2,8 @@ use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
 use crate::impl_builder_methods;
+use crate::impl_with_headers;
+use crate::v1::common::ListPage;
 
 #[derive(Debug, Serialize, Clone)]
 pub struct AssistantRequest {
@@ -60,6 +62,65 @@ pub struct AssistantObject {
     pub file_ids: Vec<String>,
     pub metadata: HashMap<String, String>,
     pub headers: Option<HashMap<String, String>>,
+    pub http_status: Option<u16>,
+}
+
+impl_with_headers!(AssistantObject);
+
+impl AssistantObject {
+    /// Compares the assistant with the configuration `desired`. Fields left
+    /// `None` in `desired` are not compared; the order of `tools` and
+    /// `file_ids` matters. Pass `desired` to `Client::modify_assistant` unless
+    /// the diff is empty.
+    pub fn diff(&self, desired: &AssistantRequest) -> AssistantDiff {
+        fn changed<T: PartialEq + Clone>(current: Option<&T>, desired: &Option<T>) -> Option<T> {
+            desired.as_ref().filter(|d| current != Some(*d)).cloned()
+        }
+        AssistantDiff {
+            model: (self.model != desired.model).then(|| desired.model.clone()),
+            name: changed(self.name.as_ref(), &desired.name),
+            description: changed(self.description.as_ref(), &desired.description),
+            instructions: changed(self.instructions.as_ref(), &desired.instructions),
+            tools: changed(Some(&self.tools), &desired.tools),
+            file_ids: changed(Some(&self.file_ids), &desired.file_ids),
+            metadata: changed(Some(&self.metadata), &desired.metadata),
+        }
+    }
+}
+
+/// The fields where an assistant differs from the desired configuration, each
+/// holding the desired value. See `AssistantObject::diff`.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct AssistantDiff {
+    pub model: Option<String>,
+    pub name: Option<String>,
+    pub description: Option<String>,
+    pub instructions: Option<String>,
+    pub tools: Option<Vec<HashMap<String, String>>>,
+    pub file_ids: Option<Vec<String>>,
+    pub metadata: Option<HashMap<String, String>>,
+}
+
+impl AssistantDiff {
+    pub fn is_empty(&self) -> bool {
+        self.changed_fields().is_empty()
+    }
+
+    /// Names of the differing fields, e.g. for logging drift.
+    pub fn changed_fields(&self) -> Vec<&'static str> {
+        [
+            ("model", self.model.is_some()),
+            ("name", self.name.is_some()),
+            ("description", self.description.is_some()),
+            ("instructions", self.instructions.is_some()),
+            ("tools", self.tools.is_some()),
+            ("file_ids", self.file_ids.is_some()),
+            ("metadata", self.metadata.is_some()),
+        ]
+        .into_iter()
+        .filter_map(|(field, changed)| changed.then_some(field))
+        .collect()
+    }
 }
 
 #[derive(Debug, Deserialize, Serialize)]
@@ -68,13 +129,34 @@ pub struct DeletionStatus {
     pub object: String,
     pub deleted: bool,
     pub headers: Option<HashMap<String, String>>,
+    pub http_status: Option<u16>,
 }
 
+impl_with_headers!(DeletionStatus);
+
 #[derive(Debug, Deserialize, Serialize)]
 pub struct ListAssistant {
     pub object: String,
     pub data: Vec<AssistantObject>,
+    #[serde(default)]
+    pub first_id: Option<String>,
+    #[serde(default)]
+    pub last_id: Option<String>,
+    #[serde(default)]
+    pub has_more: bool,
     pub headers: Option<HashMap<String, String>>,
+    pub http_status: Option<u16>,
+}
+
+impl_with_headers!(ListAssistant);
+
+impl ListPage for ListAssistant {
+    type Item = AssistantObject;
+
+    fn into_page(self) -> (Vec<AssistantObject>, Option<String>) {
+        let next = if self.has_more { self.last_id } else { None };
+        (self.data, next)
+    }
 }
 
 #[derive(Debug, Serialize, Clone)]
@@ -89,11 +171,88 @@ pub struct AssistantFileObject {
     pub created_at: i64,
     pub assistant_id: String,
     pub headers: Option<HashMap<String, String>>,
+    pub http_status: Option<u16>,
 }
 
+impl_with_headers!(AssistantFileObject);
+
 #[derive(Debug, Deserialize, Serialize)]
 pub struct ListAssistantFile {
     pub object: String,
     pub data: Vec<AssistantFileObject>,
     pub headers: Option<HashMap<String, String>>,
+    pub http_status: Option<u16>,
+}
+
+impl_with_headers!(ListAssistantFile);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tool(kind: &str) -> HashMap<String, String> {
+        HashMap::from([("type".to_owned(), kind.to_owned())])
+    }
+
+    fn current() -> AssistantObject {
+        AssistantObject {
+            id: "asst_1".to_owned(),
+            object: "assistant".to_owned(),
+            created_at: 1,
+            name: Some("Support".to_owned()),
+            description: None,
+            model: "mistral".to_owned(),
+            instructions: Some("Be kind.".to_owned()),
+            tools: vec![tool("code_interpreter")],
+            file_ids: vec![],
+            metadata: HashMap::from([("team".to_owned(), "support".to_owned())]),
+            headers: None,
+            http_status: None,
+        }
+    }
+
+    #[test]
+    fn matching_configuration_has_an_empty_diff() {
+        let desired = AssistantRequest::new("mistral".to_owned())
+            .name("Support".to_owned())
+            .instructions("Be kind.".to_owned())
+            .tools(vec![tool("code_interpreter")]);
+        let diff = current().diff(&desired);
+        assert!(diff.is_empty());
+        assert_eq!(diff, AssistantDiff::default());
+    }
+
+    #[test]
+    fn unset_fields_are_not_compared() {
+        let diff = current().diff(&AssistantRequest::new("mistral".to_owned()));
+        assert!(diff.is_empty());
+    }
+
+    #[test]
+    fn tool_changes_are_reported() {
+        let desired = AssistantRequest::new("mistral".to_owned())
+            .tools(vec![tool("code_interpreter"), tool("retrieval")]);
+        let diff = current().diff(&desired);
+        assert_eq!(diff.changed_fields(), ["tools"]);
+        assert_eq!(diff.tools, desired.tools);
+
+        let cleared = AssistantRequest::new("mistral".to_owned()).tools(vec![]);
+        assert_eq!(current().diff(&cleared).tools, Some(vec![]));
+    }
+
+    #[test]
+    fn metadata_and_model_changes_are_reported() {
+        let metadata = HashMap::from([
+            ("team".to_owned(), "support".to_owned()),
+            ("tier".to_owned(), "gold".to_owned()),
+        ]);
+        let desired = AssistantRequest::new("llama3".to_owned())
+            .instructions("Be kind.".to_owned())
+            .metadata(metadata.clone());
+        let diff = current().diff(&desired);
+        assert_eq!(diff.changed_fields(), ["model", "metadata"]);
+        assert_eq!(diff.model.as_deref(), Some("llama3"));
+        assert_eq!(diff.metadata, Some(metadata));
+        assert!(diff.instructions.is_none());
+    }
 }
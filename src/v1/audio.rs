@@ -3,6 +3,7 @@ use std::collections::HashMap;
 use serde::{Deserialize, Serialize};
 
 use crate::impl_builder_methods;
+use crate::impl_with_headers;
 
 pub const WHISPER_1: &str = "whisper-1";
 
@@ -45,8 +46,11 @@ impl_builder_methods!(
 pub struct AudioTranscriptionResponse {
     pub text: String,
     pub headers: Option<HashMap<String, String>>,
+    pub http_status: Option<u16>,
 }
 
+impl_with_headers!(AudioTranscriptionResponse);
+
 #[derive(Debug, Serialize, Clone)]
 pub struct AudioTranslationRequest {
     pub file: String,
@@ -82,8 +86,11 @@ impl_builder_methods!(
 pub struct AudioTranslationResponse {
     pub text: String,
     pub headers: Option<HashMap<String, String>>,
+    pub http_status: Option<u16>,
 }
 
+impl_with_headers!(AudioTranslationResponse);
+
 pub const TTS_1: &str = "tts-1";
 pub const TTS_1_HD: &str = "tts-1-hd";
 
@@ -100,6 +107,10 @@ pub struct AudioSpeechRequest {
     pub input: String,
     pub voice: String,
     pub output: String,
+    /// Audio encoding: `mp3` (the server default), `opus`, `aac`, `flac`,
+    /// `wav` or `pcm`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub response_format: Option<String>,
 }
 
 impl AudioSpeechRequest {
@@ -109,14 +120,37 @@ impl AudioSpeechRequest {
             input,
             voice,
             output,
+            response_format: None,
+        }
+    }
+
+    /// File extension matching `response_format`, `mp3` when unset.
+    pub fn file_extension(&self) -> &str {
+        match self.response_format.as_deref() {
+            None => "mp3",
+            Some(format) => format,
         }
     }
 }
 
-impl_builder_methods!(AudioSpeechRequest,);
+impl_builder_methods!(AudioSpeechRequest, response_format: String);
 
 #[derive(Debug, Deserialize, Serialize)]
 pub struct AudioSpeechResponse {
     pub result: bool,
     pub headers: Option<HashMap<String, String>>,
+    pub http_status: Option<u16>,
 }
+
+impl_with_headers!(AudioSpeechResponse);
+
+/// Result of `Client::audio_speech_bytes`: the encoded audio, plus the
+/// response's status code and captured headers.
+#[derive(Debug, Deserialize, Serialize)]
+pub struct AudioSpeechBytes {
+    pub bytes: Vec<u8>,
+    pub headers: Option<HashMap<String, String>>,
+    pub http_status: Option<u16>,
+}
+
+impl_with_headers!(AudioSpeechBytes);
@@ -0,0 +1,283 @@
+//! Synchronous facade over the async [`Client`](crate::v1::api::Client), in the
+//! spirit of `reqwest::blocking`. Each method blocks the calling thread on a
+//! private single-threaded runtime, so it must not be called from within an
+//! async context. Streaming methods have no blocking counterpart.
+
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use reqwest::header::HeaderMap;
+use tokio::runtime::Runtime;
+
+use crate::v1::api::{self, ClientBuilder};
+use crate::v1::assistant::{
+    AssistantFileObject, AssistantFileRequest, AssistantObject, AssistantRequest, DeletionStatus,
+    ListAssistant, ListAssistantFile,
+};
+use crate::v1::audio::{
+    AudioSpeechBytes, AudioSpeechRequest, AudioSpeechResponse, AudioTranscriptionRequest,
+    AudioTranscriptionResponse, AudioTranslationRequest, AudioTranslationResponse,
+};
+use crate::v1::chat_completion::{
+    ChatCompletionRequest, ChatCompletionResponse, ListChatCompletionMessages,
+};
+use crate::v1::common::{ModelCapability, SortOrder};
+use crate::v1::completion::{CompletionRequest, CompletionResponse};
+use crate::v1::edit::{EditRequest, EditResponse};
+use crate::v1::embedding::{EmbeddingRequest, EmbeddingResponse};
+use crate::v1::error::{APIError, APIErrorKind};
+use crate::v1::file::{
+    FileData, FileDeleteRequest, FileDeleteResponse, FileListResponse, FileRetrieveContentRequest,
+    FileRetrieveContentResponse, FileRetrieveRequest, FileRetrieveResponse, FileUploadRequest,
+    FileUploadResponse,
+};
+use crate::v1::fine_tuning::{
+    CancelFineTuningJobRequest, CreateFineTuningJobRequest, FineTuningJobEvent,
+    FineTuningJobObject, FineTuningPagination, ListFineTuningJobEventsRequest,
+    RetrieveFineTuningJobRequest,
+};
+use crate::v1::image::{
+    ImageEditRequest, ImageEditResponse, ImageGenerationRequest, ImageGenerationResponse,
+    ImageVariationRequest, ImageVariationResponse,
+};
+use crate::v1::message::{
+    CreateMessageRequest, ListMessage, ListMessageFile, MessageFileObject, MessageObject,
+    ModifyMessageRequest,
+};
+use crate::v1::model::{ListModels, SupportedModels};
+use crate::v1::moderation::{CreateModerationRequest, CreateModerationResponse};
+use crate::v1::responses::{CreateResponseRequest, ResponseObject};
+use crate::v1::run::{
+    CreateRunRequest, CreateThreadAndRunRequest, ListRun, ListRunStep, ModifyRunRequest, RunObject,
+    RunStepObject, SubmitToolOutputsRequest,
+};
+use crate::v1::thread::{CreateThreadRequest, ModifyThreadRequest, ThreadObject};
+use crate::v1::usage::UsageSummary;
+
+#[derive(Clone)]
+pub struct Client {
+    inner: api::Client,
+    runtime: Arc<Runtime>,
+}
+
+// Generates a blocking method per async `api::Client` method of the same name.
+macro_rules! blocking_methods {
+    ($($name:ident($($arg:ident: $ty:ty),* $(,)?) -> $ret:ty;)*) => {
+        impl Client {
+            $(
+                #[allow(clippy::too_many_arguments)]
+                pub fn $name(&self, $($arg: $ty),*) -> Result<$ret, APIError> {
+                    self.runtime.block_on(self.inner.$name($($arg),*))
+                }
+            )*
+        }
+    };
+}
+
+impl Client {
+    pub fn new(api_key: String) -> Self {
+        Self::from_async(api::Client::new(api_key)).expect("failed to start runtime")
+    }
+
+    pub fn new_with_endpoint(api_endpoint: String, api_key: String) -> Self {
+        Self::from_async(api::Client::new_with_endpoint(api_endpoint, api_key))
+            .expect("failed to start runtime")
+    }
+
+    pub fn new_with_organization(api_key: String, organization: String) -> Self {
+        Self::from_async(api::Client::new_with_organization(api_key, organization))
+            .expect("failed to start runtime")
+    }
+
+    pub fn new_with_proxy(api_key: String, proxy: String) -> Result<Self, APIError> {
+        Self::from_async(api::Client::new_with_proxy(api_key, proxy)?)
+    }
+
+    /// See `api::Client::try_new`.
+    pub fn try_new(api_key: String) -> Result<Self, APIError> {
+        Self::from_async(api::Client::try_new(api_key)?)
+    }
+
+    pub fn try_new_with_endpoint(api_endpoint: String, api_key: String) -> Result<Self, APIError> {
+        Self::from_async(api::Client::try_new_with_endpoint(api_endpoint, api_key)?)
+    }
+
+    pub fn try_new_with_organization(
+        api_key: String,
+        organization: String,
+    ) -> Result<Self, APIError> {
+        Self::from_async(api::Client::try_new_with_organization(
+            api_key,
+            organization,
+        )?)
+    }
+
+    /// See `api::Client::from_env`.
+    pub fn from_env() -> Result<Self, APIError> {
+        Self::from_async(api::Client::from_env()?)
+    }
+
+    /// Builds a blocking client from an async client configured through
+    /// [`ClientBuilder`].
+    pub fn from_async(inner: api::Client) -> Result<Self, APIError> {
+        let runtime = match tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+        {
+            Ok(runtime) => runtime,
+            Err(e) => {
+                return Err(APIError::new(
+                    APIErrorKind::Other,
+                    format!("failed to start runtime: {}", e),
+                ))
+            }
+        };
+        Ok(Self {
+            inner,
+            runtime: Arc::new(runtime),
+        })
+    }
+
+    pub fn builder() -> ClientBuilder {
+        ClientBuilder::new()
+    }
+
+    /// The async client the blocking calls are made with.
+    pub fn inner(&self) -> &api::Client {
+        &self.inner
+    }
+}
+
+blocking_methods! {
+    completion(req: CompletionRequest) -> CompletionResponse;
+    edit(req: EditRequest) -> EditResponse;
+    image_generation(req: ImageGenerationRequest) -> ImageGenerationResponse;
+    image_edit(req: ImageEditRequest) -> ImageEditResponse;
+    image_variation(req: ImageVariationRequest) -> ImageVariationResponse;
+    embedding(req: EmbeddingRequest) -> EmbeddingResponse;
+    file_list() -> FileListResponse;
+    file_upload(req: FileUploadRequest) -> FileUploadResponse;
+    file_upload_resumable(path: PathBuf, purpose: String, part_size: usize) -> FileData;
+    file_delete(req: FileDeleteRequest) -> FileDeleteResponse;
+    file_retrieve(req: FileRetrieveRequest) -> FileRetrieveResponse;
+    file_retrieve_content(req: FileRetrieveContentRequest) -> FileRetrieveContentResponse;
+    chat_completion(req: ChatCompletionRequest) -> ChatCompletionResponse;
+    chat_completion_raw(req: ChatCompletionRequest) -> (ChatCompletionResponse, String);
+    retrieve_chat_completion(completion_id: String) -> ChatCompletionResponse;
+    create_response(req: CreateResponseRequest) -> ResponseObject;
+    retrieve_response(response_id: String) -> ResponseObject;
+    chat_completion_with_headers(
+        req: ChatCompletionRequest,
+        headers: HeaderMap,
+    ) -> ChatCompletionResponse;
+    list_chat_completion_messages(
+        completion_id: String,
+        limit: Option<i64>,
+        order: Option<SortOrder>,
+        after: Option<String>,
+    ) -> ListChatCompletionMessages;
+    audio_transcription(req: AudioTranscriptionRequest) -> AudioTranscriptionResponse;
+    audio_translation(req: AudioTranslationRequest) -> AudioTranslationResponse;
+    audio_speech(req: AudioSpeechRequest) -> AudioSpeechResponse;
+    audio_speech_bytes(req: AudioSpeechRequest) -> AudioSpeechBytes;
+    audio_speech_stream_to_file(req: AudioSpeechRequest, dir: PathBuf) -> PathBuf;
+    create_fine_tuning_job(req: CreateFineTuningJobRequest) -> FineTuningJobObject;
+    list_fine_tuning_jobs() -> FineTuningPagination<FineTuningJobObject>;
+    list_fine_tuning_job_events(
+        req: ListFineTuningJobEventsRequest,
+    ) -> FineTuningPagination<FineTuningJobEvent>;
+    retrieve_fine_tuning_job(req: RetrieveFineTuningJobRequest) -> FineTuningJobObject;
+    cancel_fine_tuning_job(req: CancelFineTuningJobRequest) -> FineTuningJobObject;
+    list_models() -> ListModels;
+    usage_summary(start_date: &str, end_date: &str) -> UsageSummary;
+    models_supporting(capability: ModelCapability) -> SupportedModels;
+    create_moderation(req: CreateModerationRequest) -> CreateModerationResponse;
+    create_assistant(req: AssistantRequest) -> AssistantObject;
+    retrieve_assistant(assistant_id: String) -> AssistantObject;
+    modify_assistant(assistant_id: String, req: AssistantRequest) -> AssistantObject;
+    delete_assistant(assistant_id: String) -> DeletionStatus;
+    list_assistant(
+        limit: Option<i64>,
+        order: Option<SortOrder>,
+        after: Option<String>,
+        before: Option<String>,
+    ) -> ListAssistant;
+    create_assistant_file(assistant_id: String, req: AssistantFileRequest) -> AssistantFileObject;
+    retrieve_assistant_file(assistant_id: String, file_id: String) -> AssistantFileObject;
+    delete_assistant_file(assistant_id: String, file_id: String) -> DeletionStatus;
+    list_assistant_file(
+        assistant_id: String,
+        limit: Option<i64>,
+        order: Option<SortOrder>,
+        after: Option<String>,
+        before: Option<String>,
+    ) -> ListAssistantFile;
+    create_thread(req: CreateThreadRequest) -> ThreadObject;
+    retrieve_thread(thread_id: String) -> ThreadObject;
+    modify_thread(thread_id: String, req: ModifyThreadRequest) -> ThreadObject;
+    delete_thread(thread_id: String) -> DeletionStatus;
+    create_message(thread_id: String, req: CreateMessageRequest) -> MessageObject;
+    retrieve_message(thread_id: String, message_id: String) -> MessageObject;
+    modify_message(
+        thread_id: String,
+        message_id: String,
+        req: ModifyMessageRequest,
+    ) -> MessageObject;
+    list_messages(
+        thread_id: String,
+        limit: Option<i64>,
+        order: Option<SortOrder>,
+        after: Option<String>,
+        before: Option<String>,
+        metadata: Option<HashMap<String, String>>,
+    ) -> ListMessage;
+    run_output_messages(thread_id: String, run_id: String) -> Vec<MessageObject>;
+    retrieve_message_file(
+        thread_id: String,
+        message_id: String,
+        file_id: String,
+    ) -> MessageFileObject;
+    list_message_file(
+        thread_id: String,
+        message_id: String,
+        limit: Option<i64>,
+        order: Option<SortOrder>,
+        after: Option<String>,
+        before: Option<String>,
+    ) -> ListMessageFile;
+    create_run(thread_id: String, req: CreateRunRequest) -> RunObject;
+    retrieve_run(thread_id: String, run_id: String) -> RunObject;
+    modify_run(thread_id: String, run_id: String, req: ModifyRunRequest) -> RunObject;
+    list_run(
+        thread_id: String,
+        limit: Option<i64>,
+        order: Option<SortOrder>,
+        after: Option<String>,
+        before: Option<String>,
+        metadata: Option<HashMap<String, String>>,
+    ) -> ListRun;
+    cancel_run(thread_id: String, run_id: String) -> RunObject;
+    cancel_thread_runs(thread_id: String) -> Vec<Result<RunObject, APIError>>;
+    submit_tool_outputs(
+        thread_id: String,
+        run_id: String,
+        req: SubmitToolOutputsRequest,
+    ) -> RunObject;
+    create_thread_and_run(req: CreateThreadAndRunRequest) -> RunObject;
+    retrieve_run_step(
+        thread_id: String,
+        run_id: String,
+        step_id: String,
+        include: Option<Vec<String>>,
+    ) -> RunStepObject;
+    list_run_step(
+        thread_id: String,
+        run_id: String,
+        limit: Option<i64>,
+        order: Option<SortOrder>,
+        after: Option<String>,
+        before: Option<String>,
+        include: Option<Vec<String>>,
+    ) -> ListRunStep;
+}
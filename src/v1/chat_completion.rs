@@ -1,19 +1,39 @@
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use futures::stream::{self, BoxStream};
+use futures::{Stream, StreamExt};
+use serde::de::DeserializeOwned;
 use serde::ser::SerializeMap;
 use serde::{Deserialize, Serialize, Serializer};
-use serde_json::Value;
 use std::collections::HashMap;
+use std::path::Path;
 
 use crate::impl_builder_methods;
+use crate::impl_with_headers;
 use crate::v1::common;
+use crate::v1::common::ListPage;
+use crate::v1::error::{truncate_body, APIError, APIErrorKind};
 
+/// Whether and which tool the model must call. Serialized as `"none"`,
+/// `"auto"`, `"required"` or `{"type": "function", "function": {"name": ...}}`.
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
 pub enum ToolChoiceType {
+    /// Never call a tool.
     None,
+    /// Let the model decide.
     Auto,
+    /// Call at least one tool.
+    Required,
+    /// Call this tool.
     ToolChoice { tool: Tool },
+    /// Call the function with this name.
+    Function { name: String },
 }
 
+// `remote = "Self"` turns the derives into inherent functions, which the
+// hand-written impls below wrap.
 #[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(remote = "Self")]
 pub struct ChatCompletionRequest {
     pub model: String,
     pub messages: Vec<ChatCompletionMessage>,
@@ -21,54 +41,261 @@ pub struct ChatCompletionRequest {
     pub temperature: Option<f64>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub top_p: Option<f64>,
+    /// How many alternative choices to generate, 1 if unset. They are billed
+    /// as separate completions.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub n: Option<i64>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub response_format: Option<Value>,
+    pub response_format: Option<ResponseFormat>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub stream: Option<bool>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub stop: Option<Vec<String>>,
+    pub stream_options: Option<StreamOptions>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stop: Option<common::StopSequence>,
+    /// Deprecated in favour of `max_completion_tokens`, which newer models
+    /// require. Set at most one of the two: servers may reject a request with
+    /// both with a 400, and `validate` refuses it.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub max_tokens: Option<i64>,
+    /// Upper bound on generated tokens, including reasoning tokens.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_completion_tokens: Option<i64>,
+    /// Between -2.0 and 2.0; positive values favour tokens that haven't
+    /// appeared yet. The builder clamps it into range.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub presence_penalty: Option<f64>,
+    /// Between -2.0 and 2.0; positive values penalize tokens by how often
+    /// they have appeared. The builder clamps it into range.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub frequency_penalty: Option<f64>,
+    /// Bias between -100 and 100 added to the logits of tokens, keyed by token
+    /// id. The builder clamps each bias into range.
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub logit_bias: Option<HashMap<String, i32>>,
+    pub logit_bias: Option<HashMap<String, f64>>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub user: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub seed: Option<i64>,
     #[serde(skip_serializing_if = "Option::is_none")]
+    pub logprobs: Option<bool>,
+    /// How many alternatives to report per token, 0 to 20. Requires `logprobs`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub top_logprobs: Option<u8>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub tools: Option<Vec<Tool>>,
+    /// Whether the model may call several tools in one turn. `None` leaves it
+    /// to the server's default. Only serialized along with `tools`, since
+    /// servers reject it on its own.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub parallel_tool_calls: Option<bool>,
     #[serde(skip_serializing_if = "Option::is_none")]
     #[serde(serialize_with = "serialize_tool_choice")]
     pub tool_choice: Option<ToolChoiceType>,
+    /// Keep the completion server-side so it can be fetched again with
+    /// `Client::retrieve_chat_completion`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub store: Option<bool>,
+    /// Tags stored with the completion and echoed back on retrieval.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<HashMap<String, String>>,
+}
+
+impl Serialize for ChatCompletionRequest {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        if self.tools.is_none() && self.parallel_tool_calls.is_some() {
+            let req = ChatCompletionRequest {
+                parallel_tool_calls: None,
+                ..self.clone()
+            };
+            return ChatCompletionRequest::serialize(&req, serializer);
+        }
+        ChatCompletionRequest::serialize(self, serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for ChatCompletionRequest {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        ChatCompletionRequest::deserialize(deserializer)
+    }
 }
 
 impl ChatCompletionRequest {
-    pub fn new(model: String, messages: Vec<ChatCompletionMessage>) -> Self {
+    pub fn new(model: impl Into<String>, messages: Vec<ChatCompletionMessage>) -> Self {
         Self {
-            model,
+            model: model.into(),
             messages,
             temperature: None,
             top_p: None,
             stream: None,
+            stream_options: None,
             n: None,
             response_format: None,
             stop: None,
             max_tokens: None,
+            max_completion_tokens: None,
             presence_penalty: None,
             frequency_penalty: None,
             logit_bias: None,
             user: None,
             seed: None,
+            logprobs: None,
+            top_logprobs: None,
             tools: None,
+            parallel_tool_calls: None,
             tool_choice: None,
+            store: None,
+            metadata: None,
         }
     }
+
+    /// Accepts a single sequence or a `Vec` of them.
+    pub fn stop(mut self, stop: impl Into<common::StopSequence>) -> Self {
+        self.stop = Some(stop.into());
+        self
+    }
+
+    /// Clamped to -2.0..=2.0.
+    pub fn presence_penalty(mut self, presence_penalty: f64) -> Self {
+        self.presence_penalty = Some(presence_penalty.clamp(-PENALTY_LIMIT, PENALTY_LIMIT));
+        self
+    }
+
+    /// Clamped to -2.0..=2.0.
+    pub fn frequency_penalty(mut self, frequency_penalty: f64) -> Self {
+        self.frequency_penalty = Some(frequency_penalty.clamp(-PENALTY_LIMIT, PENALTY_LIMIT));
+        self
+    }
+
+    /// Each bias is clamped to -100.0..=100.0.
+    pub fn logit_bias(mut self, logit_bias: HashMap<String, f64>) -> Self {
+        let logit_bias = logit_bias
+            .into_iter()
+            .map(|(token, bias)| (token, bias.clamp(-LOGIT_BIAS_LIMIT, LOGIT_BIAS_LIMIT)))
+            .collect();
+        self.logit_bias = Some(logit_bias);
+        self
+    }
+}
+
+const PENALTY_LIMIT: f64 = 2.0;
+const LOGIT_BIAS_LIMIT: f64 = 100.0;
+const MAX_TOP_LOGPROBS: u8 = 20;
+
+impl ChatCompletionRequest {
+    /// Checks structural mistakes the server would otherwise reject with a
+    /// less helpful message: an empty conversation, both `max_tokens` and
+    /// `max_completion_tokens` set, a `function` message that doesn't name the
+    /// function or doesn't follow an `assistant` message calling it, image
+    /// content outside a `user` message or sent to a model without
+    /// `ModelCapability::Vision` in `common::default_model_capabilities`, more
+    /// than `MAX_STOP_SEQUENCES` stop sequences, and penalties, logit biases or
+    /// `top_logprobs` out of range. Models missing from the capability map may
+    /// get images.
+    pub fn validate(&self) -> Result<(), APIError> {
+        self.validate_with_capabilities(&self.model, &common::default_model_capabilities())
+    }
+
+    /// Like `validate`, checking image content against the `capabilities` of
+    /// `model`. `Client` passes the model the request is sent as, after
+    /// aliasing, and its own capability map.
+    pub fn validate_with_capabilities(
+        &self,
+        model: &str,
+        capabilities: &HashMap<String, Vec<common::ModelCapability>>,
+    ) -> Result<(), APIError> {
+        let invalid = |message: String| Err(APIError::new(APIErrorKind::Other, message));
+        if self.messages.is_empty() {
+            return invalid("messages must not be empty".to_owned());
+        }
+        if self.max_tokens.is_some() && self.max_completion_tokens.is_some() {
+            return invalid("set either max_tokens or max_completion_tokens, not both".to_owned());
+        }
+        if let Some(stop) = &self.stop {
+            if stop.as_slice().len() > common::MAX_STOP_SEQUENCES {
+                return invalid(format!(
+                    "at most {} stop sequences are allowed, got {}",
+                    common::MAX_STOP_SEQUENCES,
+                    stop.as_slice().len()
+                ));
+            }
+        }
+        for (name, penalty) in [
+            ("presence_penalty", self.presence_penalty),
+            ("frequency_penalty", self.frequency_penalty),
+        ] {
+            if let Some(penalty) = penalty {
+                if !(-PENALTY_LIMIT..=PENALTY_LIMIT).contains(&penalty) {
+                    return invalid(format!(
+                        "{} must be between -2.0 and 2.0, got {}",
+                        name, penalty
+                    ));
+                }
+            }
+        }
+        for (token, bias) in self.logit_bias.iter().flatten() {
+            if !(-LOGIT_BIAS_LIMIT..=LOGIT_BIAS_LIMIT).contains(bias) {
+                return invalid(format!(
+                    "logit_bias[{}] must be between -100 and 100, got {}",
+                    token, bias
+                ));
+            }
+        }
+        if let Some(top_logprobs) = self.top_logprobs {
+            if top_logprobs > MAX_TOP_LOGPROBS {
+                return invalid(format!(
+                    "top_logprobs must be between 0 and {}, got {}",
+                    MAX_TOP_LOGPROBS, top_logprobs
+                ));
+            }
+        }
+        for (i, message) in self.messages.iter().enumerate() {
+            if message.role == MessageRole::function {
+                let name = match &message.name {
+                    Some(name) => name,
+                    None => {
+                        return invalid(format!(
+                            "messages[{}]: a function message must set `name` to the function it answers",
+                            i
+                        ))
+                    }
+                };
+                let called = self.messages[..i].iter().any(|earlier| {
+                    earlier.role == MessageRole::assistant
+                        && earlier
+                            .tool_calls
+                            .iter()
+                            .flatten()
+                            .any(|call| call.function.name.as_deref() == Some(name.as_str()))
+                });
+                if !called {
+                    return invalid(format!(
+                        "messages[{}]: no earlier assistant message calls the function {}",
+                        i, name
+                    ));
+                }
+            }
+            if let Content::ImageUrl(parts) = &message.content {
+                let has_image = parts.iter().any(|p| p.r#type != ContentType::text);
+                if has_image && message.role != MessageRole::user {
+                    return invalid(format!(
+                        "messages[{}]: image content is only allowed in user messages",
+                        i
+                    ));
+                }
+                let text_only = capabilities
+                    .get(model)
+                    .is_some_and(|caps| !caps.contains(&common::ModelCapability::Vision));
+                if has_image && text_only {
+                    return invalid(format!(
+                        "messages[{}]: model {} does not accept image content",
+                        i, model
+                    ));
+                }
+            }
+        }
+        Ok(())
+    }
 }
 
 impl_builder_methods!(
@@ -76,19 +303,105 @@ impl_builder_methods!(
     temperature: f64,
     top_p: f64,
     n: i64,
-    response_format: Value,
+    response_format: ResponseFormat,
     stream: bool,
-    stop: Vec<String>,
+    stream_options: StreamOptions,
     max_tokens: i64,
-    presence_penalty: f64,
-    frequency_penalty: f64,
-    logit_bias: HashMap<String, i32>,
+    max_completion_tokens: i64,
     user: String,
     seed: i64,
+    logprobs: bool,
+    top_logprobs: u8,
     tools: Vec<Tool>,
-    tool_choice: ToolChoiceType
+    parallel_tool_calls: bool,
+    tool_choice: ToolChoiceType,
+    store: bool,
+    metadata: HashMap<String, String>
 );
 
+/// Shape the model must give its answer.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+#[serde(into = "ResponseFormatRepr", from = "ResponseFormatRepr")]
+pub enum ResponseFormat {
+    Text,
+    /// Any valid JSON object.
+    JsonObject,
+    /// JSON matching `schema`. With `strict` set the server guarantees the
+    /// match; otherwise check it with `ChatCompletionResponse::parsed`.
+    JsonSchema {
+        name: String,
+        schema: JSONSchemaDefine,
+        strict: Option<bool>,
+    },
+}
+
+// Wire shape of `ResponseFormat`, which nests the schema under `json_schema`.
+#[derive(Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum ResponseFormatRepr {
+    Text,
+    JsonObject,
+    JsonSchema { json_schema: JsonSchemaFormat },
+}
+
+#[derive(Serialize, Deserialize)]
+struct JsonSchemaFormat {
+    name: String,
+    schema: JSONSchemaDefine,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    strict: Option<bool>,
+}
+
+impl From<ResponseFormat> for ResponseFormatRepr {
+    fn from(format: ResponseFormat) -> Self {
+        match format {
+            ResponseFormat::Text => ResponseFormatRepr::Text,
+            ResponseFormat::JsonObject => ResponseFormatRepr::JsonObject,
+            ResponseFormat::JsonSchema {
+                name,
+                schema,
+                strict,
+            } => ResponseFormatRepr::JsonSchema {
+                json_schema: JsonSchemaFormat {
+                    name,
+                    schema,
+                    strict,
+                },
+            },
+        }
+    }
+}
+
+impl From<ResponseFormatRepr> for ResponseFormat {
+    fn from(repr: ResponseFormatRepr) -> Self {
+        match repr {
+            ResponseFormatRepr::Text => ResponseFormat::Text,
+            ResponseFormatRepr::JsonObject => ResponseFormat::JsonObject,
+            ResponseFormatRepr::JsonSchema { json_schema } => ResponseFormat::JsonSchema {
+                name: json_schema.name,
+                schema: json_schema.schema,
+                strict: json_schema.strict,
+            },
+        }
+    }
+}
+
+/// Options that only apply when `stream` is set.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct StreamOptions {
+    /// Ask the server to send one extra chunk before `[DONE]` that has an
+    /// empty `choices` array and carries the token `usage` of the whole request.
+    pub include_usage: bool,
+}
+
+impl StreamOptions {
+    pub fn include_usage() -> Self {
+        Self {
+            include_usage: true,
+        }
+    }
+}
+
 #[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq)]
 #[allow(non_camel_case_types)]
 pub enum MessageRole {
@@ -121,6 +434,7 @@ impl serde::Serialize for Content {
 pub enum ContentType {
     text,
     image_url,
+    image_file,
 }
 
 #[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq)]
@@ -129,6 +443,81 @@ pub struct ImageUrlType {
     pub url: String,
 }
 
+/// Default cap on the length of a `data:` URL built by `ImageUrlType`, in
+/// bytes. Servers commonly reject vision requests of around 20 MB.
+pub const DEFAULT_MAX_IMAGE_URL_LEN: usize = 20 * 1024 * 1024;
+
+impl ImageUrlType {
+    /// Reads a local image into a `data:image/...;base64,...` URL, so it can be
+    /// sent without hosting it anywhere. The MIME type comes from the file
+    /// extension; png, jpg/jpeg, gif and webp are supported. Fails if the URL
+    /// would be longer than `DEFAULT_MAX_IMAGE_URL_LEN`.
+    pub fn from_path(path: &Path) -> Result<Self, APIError> {
+        Self::from_path_with_limit(path, DEFAULT_MAX_IMAGE_URL_LEN)
+    }
+
+    /// Like `from_path`, failing if the URL would be longer than `max_len`.
+    pub fn from_path_with_limit(path: &Path, max_len: usize) -> Result<Self, APIError> {
+        let mime = match path
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .map(|ext| ext.to_ascii_lowercase())
+            .as_deref()
+        {
+            Some("png") => "image/png",
+            Some("jpg") | Some("jpeg") => "image/jpeg",
+            Some("gif") => "image/gif",
+            Some("webp") => "image/webp",
+            _ => {
+                return Err(APIError::new(
+                    APIErrorKind::Other,
+                    format!(
+                        "cannot tell the image type of {}: expected a png, jpg, jpeg, gif or webp extension",
+                        path.display()
+                    ),
+                ))
+            }
+        };
+        let bytes = std::fs::read(path).map_err(|e| {
+            APIError::new(
+                APIErrorKind::Other,
+                format!("failed to read image {}: {}", path.display(), e),
+            )
+        })?;
+        Self::from_bytes_with_limit(&bytes, mime, max_len)
+    }
+
+    /// Encodes an in-memory image of MIME type `mime` (e.g. `image/png`) into
+    /// a `data:` URL. Fails if the URL would be longer than
+    /// `DEFAULT_MAX_IMAGE_URL_LEN`.
+    pub fn from_bytes(bytes: &[u8], mime: &str) -> Result<Self, APIError> {
+        Self::from_bytes_with_limit(bytes, mime, DEFAULT_MAX_IMAGE_URL_LEN)
+    }
+
+    /// Like `from_bytes`, failing if the URL would be longer than `max_len`.
+    pub fn from_bytes_with_limit(
+        bytes: &[u8],
+        mime: &str,
+        max_len: usize,
+    ) -> Result<Self, APIError> {
+        let prefix = format!("data:{};base64,", mime);
+        // Checked before encoding, so an oversized image isn't copied again.
+        let len = prefix.len() + bytes.len().div_ceil(3) * 4;
+        if len > max_len {
+            return Err(APIError::new(
+                APIErrorKind::Other,
+                format!(
+                    "encoded image is {} bytes, over the limit of {}: resize it, or upload it through the files API and send it as `image_file`",
+                    len, max_len
+                ),
+            ));
+        }
+        Ok(Self {
+            url: prefix + &STANDARD.encode(bytes),
+        })
+    }
+}
+
 #[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq)]
 #[allow(non_camel_case_types)]
 pub struct ImageUrl {
@@ -137,6 +526,45 @@ pub struct ImageUrl {
     pub text: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub image_url: Option<ImageUrlType>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub image_file: Option<ImageFileType>,
+}
+
+/// Reference to an image uploaded through the files API, so it needn't be sent
+/// again with every request that uses it.
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq)]
+pub struct ImageFileType {
+    pub file_id: String,
+}
+
+impl ImageUrl {
+    pub fn text(text: String) -> Self {
+        Self {
+            r#type: ContentType::text,
+            text: Some(text),
+            image_url: None,
+            image_file: None,
+        }
+    }
+
+    pub fn url(url: String) -> Self {
+        Self {
+            r#type: ContentType::image_url,
+            text: None,
+            image_url: Some(ImageUrlType { url }),
+            image_file: None,
+        }
+    }
+
+    /// Serializes as `{"type": "image_file", "image_file": {"file_id": ...}}`.
+    pub fn file_id(file_id: String) -> Self {
+        Self {
+            r#type: ContentType::image_file,
+            text: None,
+            image_url: None,
+            image_file: Some(ImageFileType { file_id }),
+        }
+    }
 }
 
 #[derive(Debug, Deserialize, Serialize, Clone)]
@@ -145,6 +573,72 @@ pub struct ChatCompletionMessage {
     pub content: Content,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub name: Option<String>,
+    /// The calls an `assistant` message made, which the following `function`
+    /// messages answer.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tool_calls: Option<Vec<ToolCall>>,
+}
+
+/// Builds the message list of a conversation without spelling out every
+/// `ChatCompletionMessage`, e.g.
+/// `Messages::new().system("Be terse.").user("What is bitcoin?").build()`.
+#[derive(Debug, Default, Clone)]
+pub struct Messages {
+    messages: Vec<ChatCompletionMessage>,
+}
+
+impl Messages {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn system(self, content: impl Into<String>) -> Self {
+        self.text(MessageRole::system, content, None)
+    }
+
+    pub fn user(self, content: impl Into<String>) -> Self {
+        self.text(MessageRole::user, content, None)
+    }
+
+    pub fn assistant(self, content: impl Into<String>) -> Self {
+        self.text(MessageRole::assistant, content, None)
+    }
+
+    /// The assistant's turn that made `tool_calls`, e.g. taken from the
+    /// response's message. It goes before the `function` messages answering
+    /// the calls.
+    pub fn assistant_tool_calls(self, tool_calls: Vec<ToolCall>) -> Self {
+        self.message(ChatCompletionMessage {
+            role: MessageRole::assistant,
+            content: Content::Text(String::new()),
+            name: None,
+            tool_calls: Some(tool_calls),
+        })
+    }
+
+    /// The result of calling the function `name`.
+    pub fn function(self, name: impl Into<String>, content: impl Into<String>) -> Self {
+        self.text(MessageRole::function, content, Some(name.into()))
+    }
+
+    /// Appends a message built by hand, e.g. one with image content.
+    pub fn message(mut self, message: ChatCompletionMessage) -> Self {
+        self.messages.push(message);
+        self
+    }
+
+    pub fn build(self) -> Vec<ChatCompletionMessage> {
+        self.messages
+    }
+
+    fn text(self, role: MessageRole, content: impl Into<String>, name: Option<String>) -> Self {
+        self.message(ChatCompletionMessage {
+            role,
+            content: Content::Text(content.into()),
+            name,
+            tool_calls: None,
+        })
+    }
 }
 
 #[derive(Debug, Deserialize, Serialize)]
@@ -160,9 +654,28 @@ pub struct ChatCompletionMessageForResponse {
 
 #[derive(Debug, Deserialize, Serialize)]
 pub struct ChatChunkMessageForResponse {
-    pub role: MessageRole,
+    /// Only sent on the first delta of a choice, which often carries no
+    /// content at all.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub role: Option<MessageRole>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub content: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tool_calls: Option<Vec<ToolCallDelta>>,
+}
+
+/// Fragment of a tool call in a streamed delta. `id`, `type` and the function
+/// name arrive with the first fragment of each call; `arguments` is spread over
+/// many fragments as pieces of a JSON string.
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct ToolCallDelta {
+    pub index: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub r#type: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub function: Option<ToolCallFunction>,
 }
 
 #[derive(Debug, Deserialize, Serialize)]
@@ -171,6 +684,34 @@ pub struct ChatCompletionChoice {
     pub message: ChatCompletionMessageForResponse,
     pub finish_reason: Option<FinishReason>,
     pub finish_details: Option<FinishDetails>,
+    /// Only set when the request asked for `logprobs`.
+    pub logprobs: Option<LogProbs>,
+}
+
+/// Log-probabilities of the generated tokens.
+#[derive(Debug, Deserialize, Serialize, Clone, Default)]
+pub struct LogProbs {
+    #[serde(default)]
+    pub content: Vec<TokenLogProb>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct TokenLogProb {
+    pub token: String,
+    pub logprob: f64,
+    /// UTF-8 bytes of the token, for tokens that split a character.
+    pub bytes: Option<Vec<u8>>,
+    /// The most likely alternatives at this position, when `top_logprobs` was
+    /// requested.
+    #[serde(default)]
+    pub top_logprobs: Vec<TopLogProb>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct TopLogProb {
+    pub token: String,
+    pub logprob: f64,
+    pub bytes: Option<Vec<u8>>,
 }
 
 #[derive(Debug, Deserialize, Serialize)]
@@ -178,28 +719,534 @@ pub struct ChatChunkCompletionChoice {
     pub index: i64,
     pub delta: ChatChunkMessageForResponse,
     pub finish_reason: Option<FinishReason>,
+    /// Log-probabilities of the tokens in this delta.
+    pub logprobs: Option<LogProbs>,
 }
 
 #[derive(Debug, Deserialize, Serialize)]
 pub struct ChatCompletionResponse {
     pub id: String,
     pub object: String,
+    #[serde(alias = "created_at")]
     pub created: i64,
     pub model: String,
+    /// One choice per requested `n`, in `index` order.
     pub choices: Vec<ChatCompletionChoice>,
     pub usage: common::Usage,
     pub system_fingerprint: Option<String>,
+    /// The request's `metadata`, echoed by completions created with `store`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<HashMap<String, String>>,
     pub headers: Option<HashMap<String, String>>,
+    pub http_status: Option<u16>,
+}
+
+impl_with_headers!(ChatCompletionResponse);
+
+impl ChatCompletionResponse {
+    /// Deserializes the content of the first choice, for requests made with a
+    /// JSON `response_format`. Content that doesn't match `T`, or is missing,
+    /// fails with `APIErrorKind::StructuredOutput` carrying what the model sent.
+    pub fn parsed<T: DeserializeOwned>(&self) -> Result<T, APIError> {
+        let content = self
+            .choices
+            .first()
+            .and_then(|c| c.message.content.as_deref())
+            .unwrap_or_default();
+        serde_json::from_str(content).map_err(|e| {
+            let raw_content = truncate_body(content);
+            let message = format!(
+                "structured output does not match the expected type: {} (content: {})",
+                e, raw_content
+            );
+            APIError::new(APIErrorKind::StructuredOutput { raw_content }, message)
+        })
+    }
+
+    /// Rate-limit budget reported alongside this response.
+    pub fn rate_limit(&self) -> Option<common::RateLimit> {
+        common::RateLimit::from_headers(self.headers.as_ref()?)
+    }
+}
+
+/// One of the input messages a stored completion was sent with, see
+/// `Client::list_chat_completion_messages`.
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct StoredChatCompletionMessage {
+    pub id: String,
+    pub role: MessageRole,
+    pub content: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct ListChatCompletionMessages {
+    pub object: String,
+    pub data: Vec<StoredChatCompletionMessage>,
+    pub first_id: String,
+    pub last_id: String,
+    pub has_more: bool,
+    pub headers: Option<HashMap<String, String>>,
+    pub http_status: Option<u16>,
+}
+
+impl_with_headers!(ListChatCompletionMessages);
+
+impl ListPage for ListChatCompletionMessages {
+    type Item = StoredChatCompletionMessage;
+
+    fn into_page(self) -> (Vec<StoredChatCompletionMessage>, Option<String>) {
+        let next = self.has_more.then_some(self.last_id);
+        (self.data, next)
+    }
 }
 
 #[derive(Debug, Deserialize, Serialize)]
 pub struct ChatChunkResponse {
     pub id: String,
     pub object: String,
+    #[serde(alias = "created_at")]
     pub created: i64,
     pub model: String,
+    /// Deltas of the choices that advanced in this chunk. With `n` above 1 the
+    /// deltas of different choices interleave across chunks; tell them apart
+    /// by `index`, or let `ChatStreamAccumulator` do it.
     pub choices: Vec<ChatChunkCompletionChoice>,
     pub system_fingerprint: Option<String>,
+    /// Token usage for the whole request. Only set on the trailing chunk sent
+    /// when `stream_options.include_usage` is requested, and `null` or absent
+    /// on every other chunk.
+    pub usage: Option<common::Usage>,
+}
+
+/// Reassembles the deltas of a streamed chat completion into complete messages,
+/// concatenating content and stitching fragmented tool calls back together.
+/// Deltas are grouped by choice `index`, so requests with `n` above 1 come
+/// out as separate choices.
+#[derive(Debug, Default)]
+pub struct ChatStreamAccumulator {
+    id: String,
+    object: String,
+    created: i64,
+    model: String,
+    system_fingerprint: Option<String>,
+    usage: Option<common::Usage>,
+    choices: Vec<AccumulatedChoice>,
+}
+
+#[derive(Debug)]
+struct AccumulatedChoice {
+    index: i64,
+    role: Option<MessageRole>,
+    content: Option<String>,
+    // Calls in `index` order, with the server's index for each kept
+    // alongside, so an out-of-range index can't pad the list with empties.
+    tool_calls: Vec<ToolCall>,
+    tool_call_indices: Vec<usize>,
+    finish_reason: Option<FinishReason>,
+    logprobs: Option<LogProbs>,
+}
+
+/// The result of `ChatStreamAccumulator::finish`, shaped like a
+/// `ChatCompletionResponse`. `usage` is only known if the request asked for it
+/// through `stream_options`.
+#[derive(Debug, Deserialize, Serialize)]
+pub struct AccumulatedChatCompletion {
+    pub id: String,
+    pub object: String,
+    pub created: i64,
+    pub model: String,
+    pub choices: Vec<ChatCompletionChoice>,
+    pub usage: Option<common::Usage>,
+    pub system_fingerprint: Option<String>,
+}
+
+impl ChatStreamAccumulator {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Drains `stream` into a new accumulator, stopping at the first error.
+    pub async fn from_stream<S>(stream: S) -> Result<Self, APIError>
+    where
+        S: Stream<Item = Result<ChatChunkResponse, APIError>>,
+    {
+        let mut acc = Self::new();
+        let mut stream = std::pin::pin!(stream);
+        while let Some(chunk) = stream.next().await {
+            acc.push(chunk?);
+        }
+        Ok(acc)
+    }
+
+    /// Folds one chunk into the accumulated state.
+    pub fn push(&mut self, chunk: ChatChunkResponse) {
+        self.id = chunk.id;
+        self.object = chunk.object;
+        self.created = chunk.created;
+        self.model = chunk.model;
+        if chunk.system_fingerprint.is_some() {
+            self.system_fingerprint = chunk.system_fingerprint;
+        }
+        if chunk.usage.is_some() {
+            self.usage = chunk.usage;
+        }
+        for delta in chunk.choices {
+            let choice = self.choice_mut(delta.index);
+            if delta.delta.role.is_some() {
+                choice.role = delta.delta.role;
+            }
+            if let Some(content) = delta.delta.content {
+                choice
+                    .content
+                    .get_or_insert_with(String::new)
+                    .push_str(&content);
+            }
+            for call in delta.delta.tool_calls.unwrap_or_default() {
+                choice.push_tool_call(call);
+            }
+            if delta.finish_reason.is_some() {
+                choice.finish_reason = delta.finish_reason;
+            }
+            if let Some(logprobs) = delta.logprobs {
+                choice
+                    .logprobs
+                    .get_or_insert_with(LogProbs::default)
+                    .content
+                    .extend(logprobs.content);
+            }
+        }
+    }
+
+    /// Role announced by the first choice's opening delta, if it has arrived.
+    /// `finish` falls back to `assistant` when the server never sent one.
+    pub fn role(&self) -> Option<MessageRole> {
+        self.choices.first()?.role.clone()
+    }
+
+    /// Content accumulated so far for the first choice.
+    pub fn content(&self) -> Option<&str> {
+        self.choices.first()?.content.as_deref()
+    }
+
+    /// Content accumulated so far for the choice with the given `index`.
+    pub fn choice_content(&self, index: i64) -> Option<&str> {
+        self.choices
+            .iter()
+            .find(|c| c.index == index)?
+            .content
+            .as_deref()
+    }
+
+    /// Tool calls accumulated so far for the first choice. Arguments are only
+    /// valid JSON once the stream has finished.
+    pub fn tool_calls(&self) -> &[ToolCall] {
+        self.choices
+            .first()
+            .map(|c| c.tool_calls.as_slice())
+            .unwrap_or_default()
+    }
+
+    /// The accumulated completion, with its choices in `index` order.
+    pub fn finish(mut self) -> AccumulatedChatCompletion {
+        self.choices.sort_by_key(|c| c.index);
+        AccumulatedChatCompletion {
+            id: self.id,
+            object: self.object,
+            created: self.created,
+            model: self.model,
+            choices: self
+                .choices
+                .into_iter()
+                .map(|c| ChatCompletionChoice {
+                    index: c.index,
+                    message: ChatCompletionMessageForResponse {
+                        role: c.role.unwrap_or(MessageRole::assistant),
+                        content: c.content,
+                        name: None,
+                        tool_calls: if c.tool_calls.is_empty() {
+                            None
+                        } else {
+                            Some(c.tool_calls)
+                        },
+                    },
+                    finish_reason: c.finish_reason,
+                    finish_details: None,
+                    logprobs: c.logprobs,
+                })
+                .collect(),
+            usage: self.usage,
+            system_fingerprint: self.system_fingerprint,
+        }
+    }
+
+    fn choice_mut(&mut self, index: i64) -> &mut AccumulatedChoice {
+        let pos = match self.choices.iter().position(|c| c.index == index) {
+            Some(pos) => pos,
+            None => {
+                self.choices.push(AccumulatedChoice {
+                    index,
+                    role: None,
+                    content: None,
+                    tool_calls: Vec::new(),
+                    tool_call_indices: Vec::new(),
+                    finish_reason: None,
+                    logprobs: None,
+                });
+                self.choices.len() - 1
+            }
+        };
+        &mut self.choices[pos]
+    }
+}
+
+impl AccumulatedChoice {
+    fn push_tool_call(&mut self, delta: ToolCallDelta) {
+        let pos = match self.tool_call_indices.binary_search(&delta.index) {
+            Ok(pos) => pos,
+            Err(pos) => {
+                self.tool_call_indices.insert(pos, delta.index);
+                self.tool_calls.insert(
+                    pos,
+                    ToolCall {
+                        id: String::new(),
+                        r#type: String::new(),
+                        function: ToolCallFunction {
+                            name: None,
+                            arguments: None,
+                        },
+                    },
+                );
+                pos
+            }
+        };
+        let call = &mut self.tool_calls[pos];
+        if let Some(id) = delta.id {
+            call.id = id;
+        }
+        if let Some(r#type) = delta.r#type {
+            call.r#type = r#type;
+        }
+        if let Some(function) = delta.function {
+            if function.name.is_some() {
+                call.function.name = function.name;
+            }
+            if let Some(arguments) = function.arguments {
+                call.function
+                    .arguments
+                    .get_or_insert_with(String::new)
+                    .push_str(&arguments);
+            }
+        }
+    }
+}
+
+impl From<ChatCompletionResponse> for AccumulatedChatCompletion {
+    fn from(res: ChatCompletionResponse) -> Self {
+        Self {
+            id: res.id,
+            object: res.object,
+            created: res.created,
+            model: res.model,
+            choices: res.choices,
+            usage: Some(res.usage),
+            system_fingerprint: res.system_fingerprint,
+        }
+    }
+}
+
+impl From<ChatCompletionResponse> for ChatChunkResponse {
+    /// Presents a complete response as a single chunk carrying every choice
+    /// in full, followed by nothing else.
+    fn from(res: ChatCompletionResponse) -> Self {
+        Self {
+            id: res.id,
+            object: res.object,
+            created: res.created,
+            model: res.model,
+            choices: res
+                .choices
+                .into_iter()
+                .map(|c| ChatChunkCompletionChoice {
+                    index: c.index,
+                    delta: ChatChunkMessageForResponse {
+                        role: Some(c.message.role),
+                        content: c.message.content,
+                        tool_calls: c.message.tool_calls.map(|calls| {
+                            calls
+                                .into_iter()
+                                .enumerate()
+                                .map(|(index, call)| ToolCallDelta {
+                                    index,
+                                    id: Some(call.id),
+                                    r#type: Some(call.r#type),
+                                    function: Some(call.function),
+                                })
+                                .collect()
+                        }),
+                    },
+                    finish_reason: c.finish_reason,
+                    logprobs: c.logprobs,
+                })
+                .collect(),
+            system_fingerprint: res.system_fingerprint,
+            usage: Some(res.usage),
+        }
+    }
+}
+
+/// The answer to `Client::chat`, whichever transport was used to fetch it. It
+/// can be consumed exactly once, either whole with `await_full` or as deltas
+/// with `stream`; both methods take the handle by value.
+pub enum ChatResponseHandle {
+    Full(Box<ChatCompletionResponse>),
+    Stream(BoxStream<'static, Result<ChatChunkResponse, APIError>>),
+}
+
+impl ChatResponseHandle {
+    /// Waits for the complete answer, accumulating the deltas if the request
+    /// was streamed.
+    pub async fn await_full(self) -> Result<AccumulatedChatCompletion, APIError> {
+        match self {
+            ChatResponseHandle::Full(res) => Ok((*res).into()),
+            ChatResponseHandle::Stream(stream) => {
+                Ok(ChatStreamAccumulator::from_stream(stream).await?.finish())
+            }
+        }
+    }
+
+    /// Yields the answer as chunks. A non-streamed answer arrives as a single
+    /// chunk.
+    pub fn stream(self) -> BoxStream<'static, Result<ChatChunkResponse, APIError>> {
+        match self {
+            ChatResponseHandle::Full(res) => stream::once(async { Ok((*res).into()) }).boxed(),
+            ChatResponseHandle::Stream(stream) => stream,
+        }
+    }
+}
+
+/// Best-effort parser for JSON that is still arriving, e.g. the content of a
+/// streamed `json_schema` response. Each value it returns is the text received
+/// so far with open strings, arrays and objects closed, and an unfinished
+/// trailing member dropped, so it is partial: fields can be missing and the
+/// last string or number can still grow.
+#[derive(Debug, Default, Clone)]
+pub struct PartialJson {
+    buffer: String,
+}
+
+impl PartialJson {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Appends `fragment` and returns the most complete value parsable from
+    /// everything received so far, if any.
+    pub fn push(&mut self, fragment: &str) -> Option<serde_json::Value> {
+        self.buffer.push_str(fragment);
+        self.value()
+    }
+
+    /// The most complete value parsable from the text received so far.
+    pub fn value(&self) -> Option<serde_json::Value> {
+        let mut end = self.buffer.len();
+        loop {
+            let text = &self.buffer[..end];
+            let scan = scan_partial_json(text);
+            let mut candidate = text.trim_end().to_owned();
+            if scan.in_string {
+                if scan.escaped {
+                    candidate.pop();
+                }
+                candidate.push('"');
+            }
+            if candidate.ends_with(',') {
+                candidate.pop();
+            }
+            candidate.extend(scan.open.iter().rev());
+            if let Ok(value) = serde_json::from_str(&candidate) {
+                return Some(value);
+            }
+            // Drop the unfinished last member and try again.
+            end = scan.cut_points.into_iter().rfind(|&cut| cut < end)?;
+        }
+    }
+}
+
+struct PartialJsonScan {
+    // Closing brackets still owed, innermost last.
+    open: Vec<char>,
+    in_string: bool,
+    escaped: bool,
+    // Prefix lengths that end right before a `,` or right after a `{`/`[`.
+    cut_points: Vec<usize>,
+}
+
+fn scan_partial_json(text: &str) -> PartialJsonScan {
+    let mut scan = PartialJsonScan {
+        open: vec![],
+        in_string: false,
+        escaped: false,
+        cut_points: vec![],
+    };
+    for (i, c) in text.char_indices() {
+        if scan.in_string {
+            match c {
+                _ if scan.escaped => scan.escaped = false,
+                '\\' => scan.escaped = true,
+                '"' => scan.in_string = false,
+                _ => {}
+            }
+            continue;
+        }
+        match c {
+            '"' => scan.in_string = true,
+            '{' => {
+                scan.open.push('}');
+                scan.cut_points.push(i + 1);
+            }
+            '[' => {
+                scan.open.push(']');
+                scan.cut_points.push(i + 1);
+            }
+            '}' | ']' => {
+                scan.open.pop();
+            }
+            ',' => scan.cut_points.push(i),
+            _ => {}
+        }
+    }
+    scan
+}
+
+/// Turns the chunks of a streamed chat completion into progressively more
+/// complete JSON values parsed from the first choice's content, see
+/// `PartialJson`. A value is only yielded when it differs from the previous
+/// one; stream errors are passed through.
+pub fn partial_json_stream<S>(stream: S) -> impl Stream<Item = Result<serde_json::Value, APIError>>
+where
+    S: Stream<Item = Result<ChatChunkResponse, APIError>>,
+{
+    let mut parser = PartialJson::new();
+    let mut last = None;
+    stream.filter_map(move |chunk| {
+        let item = match chunk {
+            Err(e) => Some(Err(e)),
+            Ok(chunk) => chunk
+                .choices
+                .into_iter()
+                .find(|c| c.index == 0)
+                .and_then(|c| c.delta.content)
+                .and_then(|content| parser.push(&content))
+                .filter(|value| last.as_ref() != Some(value))
+                .map(|value| {
+                    last = Some(value.clone());
+                    Ok(value)
+                }),
+        };
+        futures::future::ready(item)
+    })
 }
 
 #[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq)]
@@ -247,7 +1294,10 @@ pub struct FunctionParameters {
     pub required: Option<Vec<String>>,
 }
 
-#[derive(Debug, Deserialize, Serialize, PartialEq, Eq)]
+/// Why the model stopped generating. Reasons this crate doesn't know yet are
+/// kept verbatim in `Unknown` instead of failing the whole response.
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq)]
+#[serde(from = "String", into = "String")]
 #[allow(non_camel_case_types)]
 pub enum FinishReason {
     stop,
@@ -255,6 +1305,33 @@ pub enum FinishReason {
     content_filter,
     tool_calls,
     null,
+    Unknown(String),
+}
+
+impl From<String> for FinishReason {
+    fn from(reason: String) -> Self {
+        match reason.as_str() {
+            "stop" => FinishReason::stop,
+            "length" => FinishReason::length,
+            "content_filter" => FinishReason::content_filter,
+            "tool_calls" => FinishReason::tool_calls,
+            "null" => FinishReason::null,
+            _ => FinishReason::Unknown(reason),
+        }
+    }
+}
+
+impl From<FinishReason> for String {
+    fn from(reason: FinishReason) -> Self {
+        match reason {
+            FinishReason::stop => "stop".to_owned(),
+            FinishReason::length => "length".to_owned(),
+            FinishReason::content_filter => "content_filter".to_owned(),
+            FinishReason::tool_calls => "tool_calls".to_owned(),
+            FinishReason::null => "null".to_owned(),
+            FinishReason::Unknown(reason) => reason,
+        }
+    }
 }
 
 #[derive(Debug, Deserialize, Serialize)]
@@ -279,6 +1356,12 @@ pub struct ToolCallFunction {
     pub arguments: Option<String>,
 }
 
+// The API only wants the name of the forced function, not its definition.
+#[derive(Serialize)]
+struct FunctionName<'a> {
+    name: &'a str,
+}
+
 fn serialize_tool_choice<S>(
     value: &Option<ToolChoiceType>,
     serializer: S,
@@ -289,10 +1372,24 @@ where
     match value {
         Some(ToolChoiceType::None) => serializer.serialize_str("none"),
         Some(ToolChoiceType::Auto) => serializer.serialize_str("auto"),
+        Some(ToolChoiceType::Required) => serializer.serialize_str("required"),
         Some(ToolChoiceType::ToolChoice { tool }) => {
-            let mut map = serializer.serialize_map(Some(2))?;
+            let mut map = serializer.serialize_map(None)?;
             map.serialize_entry("type", &tool.r#type)?;
-            map.serialize_entry("function", &tool.function)?;
+            if let Some(function) = &tool.function {
+                map.serialize_entry(
+                    "function",
+                    &FunctionName {
+                        name: &function.name,
+                    },
+                )?;
+            }
+            map.end()
+        }
+        Some(ToolChoiceType::Function { name }) => {
+            let mut map = serializer.serialize_map(Some(2))?;
+            map.serialize_entry("type", &ToolType::Function)?;
+            map.serialize_entry("function", &FunctionName { name })?;
             map.end()
         }
         None => serializer.serialize_none(),
@@ -302,11 +1399,923 @@ where
 #[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq)]
 pub struct Tool {
     pub r#type: ToolType,
-    pub function: Function,
+    /// The function definition, only present for `ToolType::Function`. Built-in
+    /// tools are enabled by their type alone.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub function: Option<Function>,
 }
 
+impl Tool {
+    pub fn new_function(function: Function) -> Self {
+        Self {
+            r#type: ToolType::Function,
+            function: Some(function),
+        }
+    }
+
+    pub fn web_search() -> Self {
+        Self {
+            r#type: ToolType::WebSearch,
+            function: None,
+        }
+    }
+
+    pub fn file_search() -> Self {
+        Self {
+            r#type: ToolType::FileSearch,
+            function: None,
+        }
+    }
+}
+
+/// Kind of tool offered to the model.
+///
+/// `WebSearch` and `FileSearch` are server-side tools that the backend runs
+/// itself and serialize as `{"type": "web_search"}` / `{"type": "file_search"}`.
+/// Only backends and models with native search support accept them (for example
+/// the search-enabled GPT-4o models); most self-hosted servers only understand
+/// `Function` and will reject the others.
 #[derive(Debug, Deserialize, Serialize, Copy, Clone, PartialEq, Eq)]
 #[serde(rename_all = "snake_case")]
 pub enum ToolType {
     Function,
+    WebSearch,
+    FileSearch,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn built_in_tools_serialize_by_type_alone() {
+        assert_eq!(
+            serde_json::to_value(Tool::web_search()).unwrap(),
+            json!({"type": "web_search"})
+        );
+        assert_eq!(
+            serde_json::to_value(Tool::file_search()).unwrap(),
+            json!({"type": "file_search"})
+        );
+    }
+
+    #[test]
+    fn tools_round_trip_through_their_wire_shapes() {
+        let function = Tool::new_function(Function {
+            name: "get_coin_price".to_owned(),
+            description: None,
+            parameters: FunctionParameters {
+                schema_type: JSONSchemaType::Object,
+                properties: None,
+                required: None,
+            },
+        });
+        let tools = vec![function, Tool::web_search(), Tool::file_search()];
+        let value = serde_json::to_value(&tools).unwrap();
+        assert_eq!(value[0]["type"], "function");
+        assert_eq!(value[0]["function"]["name"], "get_coin_price");
+        let back: Vec<Tool> = serde_json::from_value(value).unwrap();
+        assert_eq!(back, tools);
+    }
+
+    #[test]
+    fn built_in_tools_go_out_in_the_request_tools() {
+        let req = ChatCompletionRequest::new(
+            "gpt-4o-search-preview".to_owned(),
+            vec![ChatCompletionMessage {
+                role: MessageRole::user,
+                content: Content::Text("news?".to_owned()),
+                name: None,
+                tool_calls: None,
+            }],
+        )
+        .tools(vec![Tool::web_search()]);
+        let body = serde_json::to_value(&req).unwrap();
+        assert_eq!(body["tools"], json!([{"type": "web_search"}]));
+    }
+
+    fn tool_call_chunk(index: usize, name: Option<&str>, arguments: &str) -> ChatChunkResponse {
+        serde_json::from_value(json!({
+            "id": "chatcmpl-1",
+            "object": "chat.completion.chunk",
+            "created": 1,
+            "model": "mistral",
+            "choices": [{
+                "index": 0,
+                "delta": {
+                    "tool_calls": [{
+                        "index": index,
+                        "function": {"name": name, "arguments": arguments},
+                    }],
+                },
+            }],
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn accumulator_stitches_tool_call_fragments_by_index() {
+        let mut acc = ChatStreamAccumulator::new();
+        acc.push(tool_call_chunk(0, Some("get_weather"), "{\"city\":"));
+        acc.push(tool_call_chunk(1, Some("get_coin_price"), "{}"));
+        acc.push(tool_call_chunk(0, None, "\"Paris\"}"));
+        let calls = acc.tool_calls();
+        assert_eq!(calls.len(), 2);
+        assert_eq!(calls[0].function.name.as_deref(), Some("get_weather"));
+        assert_eq!(
+            calls[0].function.arguments.as_deref(),
+            Some("{\"city\":\"Paris\"}")
+        );
+        assert_eq!(calls[1].function.name.as_deref(), Some("get_coin_price"));
+    }
+
+    #[test]
+    fn accumulator_does_not_pad_sparse_tool_call_indices() {
+        let mut acc = ChatStreamAccumulator::new();
+        acc.push(tool_call_chunk(usize::MAX, Some("late"), "{}"));
+        acc.push(tool_call_chunk(3, Some("early"), "{}"));
+        let calls = acc.tool_calls();
+        assert_eq!(calls.len(), 2);
+        assert_eq!(calls[0].function.name.as_deref(), Some("early"));
+        assert_eq!(calls[1].function.name.as_deref(), Some("late"));
+    }
+
+    fn message(role: MessageRole, text: &str) -> ChatCompletionMessage {
+        ChatCompletionMessage {
+            role,
+            content: Content::Text(text.to_owned()),
+            name: None,
+            tool_calls: None,
+        }
+    }
+
+    fn function(name: &str, result: &str) -> ChatCompletionMessage {
+        ChatCompletionMessage {
+            name: Some(name.to_owned()),
+            ..message(MessageRole::function, result)
+        }
+    }
+
+    fn assistant_calling(names: &[&str]) -> ChatCompletionMessage {
+        let calls = names
+            .iter()
+            .map(|name| ToolCall {
+                id: format!("call_{}", name),
+                r#type: "function".to_owned(),
+                function: ToolCallFunction {
+                    name: Some(name.to_string()),
+                    arguments: Some("{}".to_owned()),
+                },
+            })
+            .collect();
+        ChatCompletionMessage {
+            tool_calls: Some(calls),
+            ..message(MessageRole::assistant, "")
+        }
+    }
+
+    fn image(role: MessageRole) -> ChatCompletionMessage {
+        ChatCompletionMessage {
+            content: Content::ImageUrl(vec![
+                ImageUrl {
+                    r#type: ContentType::text,
+                    text: Some("What is this?".to_owned()),
+                    image_url: None,
+                    image_file: None,
+                },
+                ImageUrl {
+                    r#type: ContentType::image_url,
+                    text: None,
+                    image_url: Some(ImageUrlType {
+                        url: "https://x.test/a.png".to_owned(),
+                    }),
+                    image_file: None,
+                },
+            ]),
+            ..message(role, "")
+        }
+    }
+
+    fn validation_error(model: &str, messages: Vec<ChatCompletionMessage>) -> String {
+        ChatCompletionRequest::new(model.to_owned(), messages)
+            .validate()
+            .unwrap_err()
+            .message
+    }
+
+    #[test]
+    fn validate_accepts_a_plain_conversation() {
+        let req = ChatCompletionRequest::new(
+            "mistral".to_owned(),
+            vec![
+                message(MessageRole::system, "Be terse."),
+                message(MessageRole::user, "hi"),
+            ],
+        );
+        assert!(req.validate().is_ok());
+    }
+
+    #[test]
+    fn validate_rejects_empty_messages() {
+        assert_eq!(
+            validation_error("mistral", vec![]),
+            "messages must not be empty"
+        );
+    }
+
+    #[test]
+    fn validate_rejects_both_token_limits() {
+        let req = ChatCompletionRequest::new(
+            "mistral".to_owned(),
+            vec![message(MessageRole::user, "hi")],
+        )
+        .max_tokens(10)
+        .max_completion_tokens(10);
+        assert!(req.validate().unwrap_err().message.contains("not both"));
+    }
+
+    #[test]
+    fn validate_rejects_too_many_stop_sequences() {
+        let stops: Vec<String> = (0..=common::MAX_STOP_SEQUENCES)
+            .map(|i| i.to_string())
+            .collect();
+        let req = ChatCompletionRequest::new(
+            "mistral".to_owned(),
+            vec![message(MessageRole::user, "hi")],
+        )
+        .stop(stops);
+        assert!(req
+            .validate()
+            .unwrap_err()
+            .message
+            .contains("stop sequences"));
+    }
+
+    #[test]
+    fn validate_rejects_unnamed_function_message() {
+        let mut answer = function("get_coin_price", "1000");
+        answer.name = None;
+        let messages = vec![
+            message(MessageRole::user, "price?"),
+            assistant_calling(&["get_coin_price"]),
+            answer,
+        ];
+        assert!(validation_error("mistral", messages)
+            .starts_with("messages[2]: a function message must set `name`"));
+    }
+
+    #[test]
+    fn validate_rejects_function_message_without_a_call() {
+        let messages = vec![
+            message(MessageRole::user, "price?"),
+            function("get_coin_price", "1000"),
+        ];
+        assert_eq!(
+            validation_error("mistral", messages),
+            "messages[1]: no earlier assistant message calls the function get_coin_price"
+        );
+        let messages = vec![function("get_coin_price", "1000")];
+        assert!(
+            validation_error("mistral", messages).starts_with("messages[0]: no earlier assistant")
+        );
+    }
+
+    #[test]
+    fn validate_rejects_function_message_answering_another_call() {
+        let messages = vec![
+            message(MessageRole::user, "weather?"),
+            assistant_calling(&["get_weather"]),
+            function("get_coin_price", "1000"),
+        ];
+        assert!(validation_error("mistral", messages).contains("calls the function get_coin_price"));
+    }
+
+    #[test]
+    fn validate_accepts_function_message_after_its_call() {
+        let req = ChatCompletionRequest::new(
+            "mistral".to_owned(),
+            vec![
+                message(MessageRole::user, "price?"),
+                assistant_calling(&["get_weather", "get_coin_price"]),
+                function("get_weather", "sunny"),
+                function("get_coin_price", "1000"),
+            ],
+        );
+        assert!(req.validate().is_ok());
+    }
+
+    #[test]
+    fn validate_rejects_image_outside_user_message() {
+        let err = validation_error("llava", vec![image(MessageRole::system)]);
+        assert!(err.contains("only allowed in user messages"));
+    }
+
+    #[test]
+    fn validate_rejects_image_for_text_only_model() {
+        for model in [
+            common::GPT4,
+            common::GPT4_1106_PREVIEW,
+            common::GPT3_5_TURBO_0301,
+        ] {
+            let err = validation_error(model, vec![image(MessageRole::user)]);
+            assert!(err.contains("does not accept image content"));
+        }
+        let req =
+            ChatCompletionRequest::new(common::GPT4_O.to_owned(), vec![image(MessageRole::user)]);
+        assert!(req.validate().is_ok());
+        let req = ChatCompletionRequest::new("llava".to_owned(), vec![image(MessageRole::user)]);
+        assert!(req.validate().is_ok());
+    }
+
+    #[test]
+    fn validate_with_capabilities_uses_the_given_map() {
+        let req = ChatCompletionRequest::new("vision".to_owned(), vec![image(MessageRole::user)]);
+        let capabilities = HashMap::from([
+            ("llava".to_owned(), vec![common::ModelCapability::Vision]),
+            (
+                common::GPT4.to_owned(),
+                vec![common::ModelCapability::Vision],
+            ),
+            ("local-chat".to_owned(), vec![]),
+        ]);
+        assert!(req
+            .validate_with_capabilities("llava", &capabilities)
+            .is_ok());
+        assert!(req
+            .validate_with_capabilities(common::GPT4, &capabilities)
+            .is_ok());
+        let err = req
+            .validate_with_capabilities("local-chat", &capabilities)
+            .unwrap_err();
+        assert!(err
+            .message
+            .contains("model local-chat does not accept image content"));
+    }
+
+    #[test]
+    fn tool_calls_are_only_serialized_when_set() {
+        let messages = vec![
+            message(MessageRole::user, "price?"),
+            assistant_calling(&["get_coin_price"]),
+        ];
+        let body = serde_json::to_value(&messages).unwrap();
+        assert!(body[0].get("tool_calls").is_none());
+        assert_eq!(
+            body[1]["tool_calls"][0]["function"]["name"],
+            "get_coin_price"
+        );
+    }
+
+    #[test]
+    fn messages_builds_a_tool_calling_conversation_in_order() {
+        let call = assistant_calling(&["get_coin_price"]).tool_calls.unwrap();
+        let messages = Messages::new()
+            .system("Be terse.")
+            .user("price?")
+            .assistant_tool_calls(call)
+            .function("get_coin_price", "1000")
+            .build();
+        let roles: Vec<&MessageRole> = messages.iter().map(|m| &m.role).collect();
+        assert_eq!(
+            roles,
+            [
+                &MessageRole::system,
+                &MessageRole::user,
+                &MessageRole::assistant,
+                &MessageRole::function
+            ]
+        );
+        assert_eq!(messages[3].name.as_deref(), Some("get_coin_price"));
+        assert!(messages[1].tool_calls.is_none());
+        let req = ChatCompletionRequest::new("mistral".to_owned(), messages);
+        assert!(req.validate().is_ok());
+    }
+
+    fn response_with_content(content: Option<&str>) -> ChatCompletionResponse {
+        serde_json::from_value(json!({
+            "id": "chatcmpl-1",
+            "object": "chat.completion",
+            "created": 1,
+            "model": "mistral",
+            "choices": [{
+                "index": 0,
+                "message": {"role": "assistant", "content": content},
+                "finish_reason": "stop",
+            }],
+            "usage": {"prompt_tokens": 5, "completion_tokens": 2, "total_tokens": 7},
+        }))
+        .unwrap()
+    }
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct Weather {
+        city: String,
+        celsius: f64,
+    }
+
+    #[test]
+    fn parsed_decodes_structured_output() {
+        let res = response_with_content(Some(r#"{"city": "Paris", "celsius": 21.5}"#));
+        assert_eq!(
+            res.parsed::<Weather>().unwrap(),
+            Weather {
+                city: "Paris".to_owned(),
+                celsius: 21.5,
+            }
+        );
+    }
+
+    #[test]
+    fn parsed_reports_content_not_matching_the_schema() {
+        let content = r#"{"city": "Paris", "celsius": "warm"}"#;
+        let err = response_with_content(Some(content))
+            .parsed::<Weather>()
+            .unwrap_err();
+        assert_eq!(
+            err.kind,
+            APIErrorKind::StructuredOutput {
+                raw_content: content.to_owned(),
+            }
+        );
+        assert!(err.message.starts_with("structured output does not match"));
+    }
+
+    #[test]
+    fn parsed_reports_invalid_or_missing_json() {
+        let err = response_with_content(Some("Sure! Here is the weather: sunny"))
+            .parsed::<Weather>()
+            .unwrap_err();
+        assert!(matches!(err.kind, APIErrorKind::StructuredOutput { .. }));
+        let err = response_with_content(None).parsed::<Weather>().unwrap_err();
+        assert_eq!(
+            err.kind,
+            APIErrorKind::StructuredOutput {
+                raw_content: String::new(),
+            }
+        );
+    }
+
+    #[test]
+    fn image_file_part_serializes_as_a_file_reference() {
+        let part = ImageUrl::file_id("file-abc123".to_owned());
+        assert_eq!(
+            serde_json::to_value(&part).unwrap(),
+            json!({"type": "image_file", "image_file": {"file_id": "file-abc123"}})
+        );
+        let back: ImageUrl = serde_json::from_value(serde_json::to_value(&part).unwrap()).unwrap();
+        assert_eq!(back, part);
+    }
+
+    #[test]
+    fn image_file_part_sits_beside_text_in_a_user_message() {
+        let mut message = message(MessageRole::user, "");
+        message.content = Content::ImageUrl(vec![
+            ImageUrl::text("What is this?".to_owned()),
+            ImageUrl::file_id("file-abc123".to_owned()),
+        ]);
+        assert_eq!(
+            serde_json::to_value(&message).unwrap(),
+            json!({
+                "role": "user",
+                "content": [
+                    {"type": "text", "text": "What is this?"},
+                    {"type": "image_file", "image_file": {"file_id": "file-abc123"}},
+                ],
+            })
+        );
+    }
+
+    #[test]
+    fn response_accepts_both_field_namings() {
+        let openai = json!({
+            "id": "chatcmpl-1",
+            "object": "chat.completion",
+            "created": 1700000000,
+            "model": "mistral",
+            "choices": [],
+            "usage": {"prompt_tokens": 5, "completion_tokens": 2, "total_tokens": 7}
+        });
+        let other = json!({
+            "id": "chatcmpl-1",
+            "object": "chat.completion",
+            "created_at": 1700000000,
+            "model": "mistral",
+            "choices": [],
+            "usage": {"input_tokens": 5, "output_tokens": 2}
+        });
+        for value in [openai, other] {
+            let res: ChatCompletionResponse = serde_json::from_value(value).unwrap();
+            assert_eq!(res.created, 1700000000);
+            assert_eq!(res.usage.prompt_tokens, 5);
+            assert_eq!(res.usage.completion_tokens, 2);
+            assert_eq!(res.usage.total_tokens, 7);
+        }
+    }
+
+    #[test]
+    fn choice_logprobs_deserialize_with_bytes_and_alternatives() {
+        let res: ChatCompletionResponse = serde_json::from_value(json!({
+            "id": "chatcmpl-1",
+            "object": "chat.completion",
+            "created": 1,
+            "model": "mistral",
+            "choices": [{
+                "index": 0,
+                "message": {"role": "assistant", "content": "Hi"},
+                "finish_reason": "stop",
+                "logprobs": {"content": [{
+                    "token": "Hi",
+                    "logprob": -0.25,
+                    "bytes": [72, 105],
+                    "top_logprobs": [
+                        {"token": "Hi", "logprob": -0.25, "bytes": [72, 105]},
+                        {"token": "Hey", "logprob": -1.5, "bytes": null}
+                    ]
+                }]}
+            }],
+            "usage": {"prompt_tokens": 1, "completion_tokens": 1, "total_tokens": 2}
+        }))
+        .unwrap();
+        let token = &res.choices[0].logprobs.as_ref().unwrap().content[0];
+        assert_eq!(token.token, "Hi");
+        assert_eq!(token.logprob, -0.25);
+        assert_eq!(token.bytes.as_deref(), Some(&b"Hi"[..]));
+        assert_eq!(token.top_logprobs.len(), 2);
+        assert_eq!(token.top_logprobs[1].token, "Hey");
+        assert_eq!(token.top_logprobs[1].bytes, None);
+
+        let res: ChatCompletionResponse = serde_json::from_value(json!({
+            "id": "chatcmpl-2",
+            "object": "chat.completion",
+            "created": 1,
+            "model": "mistral",
+            "choices": [{
+                "index": 0,
+                "message": {"role": "assistant", "content": "Hi"},
+                "finish_reason": "stop",
+            }],
+            "usage": {"prompt_tokens": 1, "completion_tokens": 1, "total_tokens": 2}
+        }))
+        .unwrap();
+        assert!(res.choices[0].logprobs.is_none());
+    }
+
+    #[test]
+    fn validate_rejects_top_logprobs_above_twenty() {
+        let mut req = ChatCompletionRequest::new(
+            "mistral".to_owned(),
+            vec![message(MessageRole::user, "hi")],
+        )
+        .logprobs(true);
+        req.top_logprobs = Some(20);
+        assert!(req.validate().is_ok());
+        req.top_logprobs = Some(21);
+        assert_eq!(
+            req.validate().unwrap_err().message,
+            "top_logprobs must be between 0 and 20, got 21"
+        );
+    }
+
+    fn logprob_chunk(
+        content: &str,
+        logprob: f64,
+        alternatives: &[(&str, f64)],
+    ) -> ChatChunkResponse {
+        let top: Vec<serde_json::Value> = alternatives
+            .iter()
+            .map(|(token, logprob)| json!({"token": token, "logprob": logprob, "bytes": null}))
+            .collect();
+        serde_json::from_value(json!({
+            "id": "chatcmpl-1",
+            "object": "chat.completion.chunk",
+            "created": 1,
+            "model": "mistral",
+            "choices": [{
+                "index": 0,
+                "delta": {"content": content},
+                "finish_reason": null,
+                "logprobs": {"content": [{
+                    "token": content,
+                    "logprob": logprob,
+                    "bytes": content.as_bytes(),
+                    "top_logprobs": top,
+                }]},
+            }],
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn accumulator_assembles_streamed_logprobs() {
+        let mut acc = ChatStreamAccumulator::new();
+        acc.push(logprob_chunk("Hel", -0.5, &[("Hel", -0.5), ("He", -2.0)]));
+        acc.push(logprob_chunk("lo", -0.125, &[("lo", -0.125)]));
+        let done = acc.finish();
+        let content = &done.choices[0].logprobs.as_ref().unwrap().content;
+        let tokens: Vec<(&str, f64)> = content
+            .iter()
+            .map(|t| (t.token.as_str(), t.logprob))
+            .collect();
+        assert_eq!(tokens, [("Hel", -0.5), ("lo", -0.125)]);
+        assert_eq!(content[0].bytes.as_deref(), Some(&b"Hel"[..]));
+        let alternatives: Vec<&str> = content[0]
+            .top_logprobs
+            .iter()
+            .map(|t| t.token.as_str())
+            .collect();
+        assert_eq!(alternatives, ["Hel", "He"]);
+        assert_eq!(content[1].top_logprobs.len(), 1);
+        assert_eq!(done.choices[0].message.content.as_deref(), Some("Hello"));
+    }
+
+    #[test]
+    fn parallel_tool_calls_is_only_serialized_with_tools() {
+        let req = ChatCompletionRequest::new(
+            "mistral".to_owned(),
+            vec![message(MessageRole::user, "hi")],
+        )
+        .parallel_tool_calls(false);
+        let body = serde_json::to_value(&req).unwrap();
+        assert!(body.get("parallel_tool_calls").is_none());
+        assert_eq!(req.parallel_tool_calls, Some(false));
+
+        let body = serde_json::to_value(req.tools(vec![Tool::web_search()])).unwrap();
+        assert_eq!(body["parallel_tool_calls"], false);
+    }
+
+    #[test]
+    fn partial_json_grows_with_each_fragment() {
+        let mut parser = PartialJson::new();
+        assert_eq!(parser.push(r#"{"name": "A"#), Some(json!({"name": "A"})));
+        assert_eq!(parser.push(r#"da", "ta"#), Some(json!({"name": "Ada"})));
+        assert_eq!(
+            parser.push(r#"gs": [1, 2"#),
+            Some(json!({"name": "Ada", "tags": [1, 2]}))
+        );
+        assert_eq!(
+            parser.push(r#"], "age": 3"#),
+            Some(json!({"name": "Ada", "tags": [1, 2], "age": 3}))
+        );
+        assert_eq!(
+            parser.push("6}"),
+            Some(json!({"name": "Ada", "tags": [1, 2], "age": 36}))
+        );
+    }
+
+    #[test]
+    fn partial_json_handles_escapes_and_unfinished_literals() {
+        let mut parser = PartialJson::new();
+        assert_eq!(parser.push(r#"{"q": "say \"#), Some(json!({"q": "say "})));
+        assert_eq!(parser.push(r#""hi\"""#), Some(json!({"q": "say \"hi\""})));
+        assert_eq!(
+            parser.push(r#", "ok": tr"#),
+            Some(json!({"q": "say \"hi\""}))
+        );
+        assert_eq!(
+            parser.push("ue}"),
+            Some(json!({"q": "say \"hi\"", "ok": true}))
+        );
+        assert_eq!(PartialJson::new().push(r#"{"a": tr"#), Some(json!({})));
+        assert_eq!(PartialJson::new().push("  "), None);
+    }
+
+    fn content_chunk(content: &str) -> Result<ChatChunkResponse, APIError> {
+        Ok(serde_json::from_value(json!({
+            "id": "chatcmpl-1",
+            "object": "chat.completion.chunk",
+            "created": 1,
+            "model": "mistral",
+            "choices": [{"index": 0, "delta": {"content": content}}],
+        }))
+        .unwrap())
+    }
+
+    #[tokio::test]
+    async fn partial_json_stream_yields_only_changed_values() {
+        let chunks = stream::iter(vec![
+            content_chunk(r#"{"city": "Pa"#),
+            content_chunk(r#"ris", "#),
+            content_chunk(r#""celsius": 21"#),
+            content_chunk("}"),
+        ]);
+        let values: Vec<serde_json::Value> = partial_json_stream(chunks)
+            .map(Result::unwrap)
+            .collect()
+            .await;
+        assert_eq!(
+            values,
+            [
+                json!({"city": "Pa"}),
+                json!({"city": "Paris"}),
+                json!({"city": "Paris", "celsius": 21}),
+            ]
+        );
+    }
+
+    fn delta_chunk(delta: serde_json::Value) -> ChatChunkResponse {
+        serde_json::from_value(json!({
+            "id": "chatcmpl-1",
+            "object": "chat.completion.chunk",
+            "created": 1,
+            "model": "mistral",
+            "choices": [{"index": 0, "delta": delta, "finish_reason": null}],
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn role_only_first_chunk_parses() {
+        let chunk = delta_chunk(json!({"role": "assistant", "content": null}));
+        let delta = &chunk.choices[0].delta;
+        assert_eq!(delta.role, Some(MessageRole::assistant));
+        assert!(delta.content.is_none());
+        assert!(delta.tool_calls.is_none());
+        assert!(delta_chunk(json!({"content": "Hi"})).choices[0]
+            .delta
+            .role
+            .is_none());
+    }
+
+    #[test]
+    fn accumulator_takes_the_role_from_the_first_delta() {
+        let mut acc = ChatStreamAccumulator::new();
+        assert_eq!(acc.role(), None);
+        acc.push(delta_chunk(json!({"role": "function"})));
+        acc.push(delta_chunk(json!({"content": "42"})));
+        assert_eq!(acc.role(), Some(MessageRole::function));
+        let done = acc.finish();
+        assert_eq!(done.choices[0].message.role, MessageRole::function);
+        assert_eq!(done.choices[0].message.content.as_deref(), Some("42"));
+    }
+
+    #[test]
+    fn accumulator_falls_back_to_assistant_without_a_role() {
+        let mut acc = ChatStreamAccumulator::new();
+        acc.push(delta_chunk(json!({"content": "Hi"})));
+        assert_eq!(acc.finish().choices[0].message.role, MessageRole::assistant);
+    }
+
+    #[test]
+    fn known_finish_reasons_round_trip() {
+        for (reason, wire) in [
+            (FinishReason::stop, "stop"),
+            (FinishReason::length, "length"),
+            (FinishReason::content_filter, "content_filter"),
+            (FinishReason::tool_calls, "tool_calls"),
+            (FinishReason::null, "null"),
+        ] {
+            assert_eq!(serde_json::to_value(&reason).unwrap(), wire);
+            assert_eq!(
+                serde_json::from_value::<FinishReason>(json!(wire)).unwrap(),
+                reason
+            );
+        }
+    }
+
+    #[test]
+    fn unknown_finish_reason_is_kept_instead_of_failing_the_response() {
+        let chunk: ChatChunkResponse = serde_json::from_value(json!({
+            "id": "chatcmpl-1",
+            "object": "chat.completion.chunk",
+            "created": 1,
+            "model": "mistral",
+            "choices": [{"index": 0, "delta": {}, "finish_reason": "guardrail_intervened"}],
+        }))
+        .unwrap();
+        let reason = chunk.choices[0].finish_reason.clone().unwrap();
+        assert_eq!(
+            reason,
+            FinishReason::Unknown("guardrail_intervened".to_owned())
+        );
+        assert_eq!(
+            serde_json::to_value(&reason).unwrap(),
+            "guardrail_intervened"
+        );
+    }
+
+    #[test]
+    fn stored_metadata_round_trips() {
+        let mut res = response_with_content(Some("Hi"));
+        assert!(res.metadata.is_none());
+        assert!(serde_json::to_value(&res)
+            .unwrap()
+            .get("metadata")
+            .is_none());
+
+        res.metadata = Some(HashMap::from([
+            ("experiment".to_owned(), "b".to_owned()),
+            ("tenant".to_owned(), "acme".to_owned()),
+        ]));
+        let value = serde_json::to_value(&res).unwrap();
+        assert_eq!(
+            value["metadata"],
+            json!({"experiment": "b", "tenant": "acme"})
+        );
+        let back: ChatCompletionResponse = serde_json::from_value(value).unwrap();
+        assert_eq!(back.metadata, res.metadata);
+    }
+
+    #[test]
+    fn small_image_becomes_a_data_url() {
+        let image = ImageUrlType::from_bytes(b"\x89PNG", "image/png").unwrap();
+        assert_eq!(image.url, "data:image/png;base64,iVBORw==");
+    }
+
+    #[test]
+    fn oversized_image_is_rejected_before_encoding() {
+        let bytes = vec![0u8; 3000];
+        let prefix_len = "data:image/png;base64,".len();
+        let err = ImageUrlType::from_bytes_with_limit(&bytes, "image/png", prefix_len + 3999)
+            .unwrap_err();
+        assert_eq!(err.kind, APIErrorKind::Other);
+        assert!(err.message.starts_with(&format!(
+            "encoded image is {} bytes, over the limit of {}",
+            prefix_len + 4000,
+            prefix_len + 3999
+        )));
+        assert!(err.message.contains("image_file"));
+        assert!(
+            ImageUrlType::from_bytes_with_limit(&bytes, "image/png", prefix_len + 4000).is_ok()
+        );
+    }
+
+    #[test]
+    fn image_path_limit_and_type_are_checked() {
+        let dir = std::env::temp_dir().join(format!("leap-connect-image-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let png = dir.join("big.PNG");
+        std::fs::write(&png, vec![0u8; 64 * 1024]).unwrap();
+        let err = ImageUrlType::from_path_with_limit(&png, 1024).unwrap_err();
+        assert!(err.message.contains("over the limit of 1024"));
+        assert!(ImageUrlType::from_path(&png)
+            .unwrap()
+            .url
+            .starts_with("data:image/png;base64,"));
+
+        let bmp = dir.join("image.bmp");
+        std::fs::write(&bmp, b"BM").unwrap();
+        let err = ImageUrlType::from_path(&bmp).unwrap_err();
+        assert!(err.message.starts_with("cannot tell the image type"));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn penalty_setters_clamp_into_range() {
+        let hi = || vec![message(MessageRole::user, "hi")];
+        let req = ChatCompletionRequest::new("mistral".to_owned(), hi())
+            .presence_penalty(3.5)
+            .frequency_penalty(-7.0);
+        assert_eq!(req.presence_penalty, Some(2.0));
+        assert_eq!(req.frequency_penalty, Some(-2.0));
+        assert!(req.validate().is_ok());
+
+        let req = ChatCompletionRequest::new("mistral".to_owned(), hi()).presence_penalty(0.6);
+        assert_eq!(req.presence_penalty, Some(0.6));
+    }
+
+    #[test]
+    fn logit_bias_setter_clamps_each_bias() {
+        let req = ChatCompletionRequest::new(
+            "mistral".to_owned(),
+            vec![message(MessageRole::user, "hi")],
+        )
+        .logit_bias(HashMap::from([
+            ("50256".to_owned(), -250.0),
+            ("1234".to_owned(), 12.5),
+            ("42".to_owned(), 100.5),
+        ]));
+        let bias = req.logit_bias.as_ref().unwrap();
+        assert_eq!(bias["50256"], -100.0);
+        assert_eq!(bias["1234"], 12.5);
+        assert_eq!(bias["42"], 100.0);
+        let body = serde_json::to_value(&req).unwrap();
+        assert_eq!(body["logit_bias"]["1234"], 12.5);
+    }
+
+    #[test]
+    fn validate_rejects_out_of_range_fields_set_directly() {
+        let hi = || vec![message(MessageRole::user, "hi")];
+        let mut req = ChatCompletionRequest::new("mistral".to_owned(), hi());
+        req.frequency_penalty = Some(2.5);
+        let err = req.validate().unwrap_err();
+        assert!(err.message.contains("frequency_penalty"), "{}", err.message);
+
+        let mut req = ChatCompletionRequest::new("mistral".to_owned(), hi());
+        req.logit_bias = Some(HashMap::from([("7".to_owned(), f64::NAN)]));
+        let err = req.validate().unwrap_err();
+        assert!(err.message.contains("logit_bias[7]"), "{}", err.message);
+    }
+
+    #[test]
+    fn unset_sampling_controls_are_not_serialized() {
+        let req = ChatCompletionRequest::new(
+            "mistral".to_owned(),
+            vec![message(MessageRole::user, "hi")],
+        );
+        let body = serde_json::to_value(req).unwrap();
+        for field in ["presence_penalty", "frequency_penalty", "logit_bias"] {
+            assert!(body.get(field).is_none(), "{} was sent", field);
+        }
+    }
 }
@@ -1,10 +1,362 @@
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt;
+use std::time::Duration;
 
-#[derive(Debug, Deserialize, Serialize)]
+use crate::v1::error::{APIError, APIErrorKind};
+
+/// Token counts of a request, shared by completion, chat, edit and embedding
+/// responses. Embeddings generate nothing, so `completion_tokens` is 0 there.
+///
+/// Several OpenAI-compatible servers name fields differently, so responses
+/// also accept these aliases:
+///
+/// - `prompt_tokens`: `input_tokens`
+/// - `completion_tokens`: `output_tokens`
+/// - `created` (on completion, chat, edit and image responses): `created_at`
+///
+/// A missing `total_tokens`, as with servers using the `input_tokens` naming,
+/// is taken as the sum of the other two counts.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(from = "UsageRepr")]
 pub struct Usage {
     pub prompt_tokens: i32,
     pub completion_tokens: i32,
     pub total_tokens: i32,
+    /// Only sent by servers that break the prompt down further.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub prompt_tokens_details: Option<PromptTokensDetails>,
+    /// Only sent by servers that break the completion down further.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub completion_tokens_details: Option<CompletionTokensDetails>,
+}
+
+// Wire shape of `Usage`, with the aliases and the optional total.
+#[derive(Deserialize)]
+struct UsageRepr {
+    #[serde(alias = "input_tokens")]
+    prompt_tokens: i32,
+    #[serde(default, alias = "output_tokens")]
+    completion_tokens: i32,
+    total_tokens: Option<i32>,
+    #[serde(default)]
+    prompt_tokens_details: Option<PromptTokensDetails>,
+    #[serde(default)]
+    completion_tokens_details: Option<CompletionTokensDetails>,
+}
+
+impl From<UsageRepr> for Usage {
+    fn from(repr: UsageRepr) -> Self {
+        Usage {
+            prompt_tokens: repr.prompt_tokens,
+            completion_tokens: repr.completion_tokens,
+            total_tokens: repr
+                .total_tokens
+                .unwrap_or(repr.prompt_tokens + repr.completion_tokens),
+            prompt_tokens_details: repr.prompt_tokens_details,
+            completion_tokens_details: repr.completion_tokens_details,
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(default)]
+pub struct PromptTokensDetails {
+    /// Prompt tokens served from the prompt cache.
+    pub cached_tokens: i32,
+    pub audio_tokens: i32,
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(default)]
+pub struct CompletionTokensDetails {
+    /// Tokens the model spent reasoning, not part of the visible output.
+    pub reasoning_tokens: i32,
+    pub audio_tokens: i32,
+    pub accepted_prediction_tokens: i32,
+    pub rejected_prediction_tokens: i32,
+}
+
+/// Rate-limit budget reported in the `x-ratelimit-*` response headers. Fields
+/// are `None` when the server didn't send the header or it couldn't be parsed.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RateLimit {
+    pub limit_requests: Option<u64>,
+    pub remaining_requests: Option<u64>,
+    pub reset_requests: Option<Duration>,
+    pub limit_tokens: Option<u64>,
+    pub remaining_tokens: Option<u64>,
+    pub reset_tokens: Option<Duration>,
+}
+
+impl RateLimit {
+    /// Reads the rate-limit headers from a response's `headers` map. Returns
+    /// `None` if none of them are present.
+    pub fn from_headers(headers: &HashMap<String, String>) -> Option<Self> {
+        let get = |name: &str| headers.get(name).map(|v| v.trim());
+        let number = |name: &str| get(name).and_then(|v| v.parse().ok());
+        let duration = |name: &str| get(name).and_then(parse_reset);
+        let rate_limit = RateLimit {
+            limit_requests: number("x-ratelimit-limit-requests"),
+            remaining_requests: number("x-ratelimit-remaining-requests"),
+            reset_requests: duration("x-ratelimit-reset-requests"),
+            limit_tokens: number("x-ratelimit-limit-tokens"),
+            remaining_tokens: number("x-ratelimit-remaining-tokens"),
+            reset_tokens: duration("x-ratelimit-reset-tokens"),
+        };
+        if rate_limit == RateLimit::default() {
+            None
+        } else {
+            Some(rate_limit)
+        }
+    }
+}
+
+// Parses reset durations such as `20ms`, `1s`, `6m0s` or `1h2m3.5s`.
+fn parse_reset(value: &str) -> Option<Duration> {
+    if value.is_empty() {
+        return None;
+    }
+    let mut total = 0f64;
+    let mut rest = value;
+    while !rest.is_empty() {
+        let split = rest
+            .find(|c: char| !(c.is_ascii_digit() || c == '.'))
+            .unwrap_or(rest.len());
+        let (number, tail) = rest.split_at(split);
+        let number: f64 = number.parse().ok()?;
+        let unit_len = tail
+            .find(|c: char| c.is_ascii_digit() || c == '.')
+            .unwrap_or(tail.len());
+        let (unit, tail) = tail.split_at(unit_len);
+        total += number
+            * match unit {
+                "ms" => 0.001,
+                "s" => 1.0,
+                "m" => 60.0,
+                "h" => 3600.0,
+                _ => return None,
+            };
+        rest = tail;
+    }
+    Duration::try_from_secs_f64(total).ok()
+}
+
+/// Feature a model may support, see `Client::models_supporting`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum ModelCapability {
+    Vision,
+    FunctionCalling,
+    JsonMode,
+    Embeddings,
+    /// Takes `max_completion_tokens` and rejects the older `max_tokens`.
+    MaxCompletionTokens,
+}
+
+/// Capabilities of the models this crate has constants for, keyed by model id.
+/// Replace or extend it with `Client::set_model_capabilities` for other
+/// backends.
+pub fn default_model_capabilities() -> HashMap<String, Vec<ModelCapability>> {
+    use ModelCapability::*;
+    let chat_tools = [FunctionCalling, JsonMode];
+    let mut capabilities = HashMap::new();
+    for model in [GPT4_O, GPT4_O_2024_05_13] {
+        capabilities.insert(model.to_owned(), vec![Vision, FunctionCalling, JsonMode]);
+    }
+    for model in [
+        GPT3_5_TURBO_1106,
+        GPT3_5_TURBO,
+        GPT4_0125_PREVIEW,
+        GPT4_TURBO_PREVIEW,
+        GPT4_1106_PREVIEW,
+    ] {
+        capabilities.insert(model.to_owned(), chat_tools.to_vec());
+    }
+    for model in [
+        GPT4,
+        GPT4_0613,
+        GPT4_32K,
+        GPT4_32K_0613,
+        GPT3_5_TURBO_0613,
+        GPT3_5_TURBO_16K_0613,
+    ] {
+        capabilities.insert(model.to_owned(), vec![FunctionCalling]);
+    }
+    // Listed without capabilities so validation knows they take no images.
+    for model in [
+        GPT3_5_TURBO_16K,
+        GPT3_5_TURBO_0301,
+        GPT3_5_TURBO_INSTRUCT,
+        GPT4_0314,
+        GPT4_32K_0314,
+    ] {
+        capabilities.insert(model.to_owned(), vec![]);
+    }
+    capabilities.insert(GPT4_VISION_PREVIEW.to_owned(), vec![Vision]);
+    capabilities.insert(
+        O1.to_owned(),
+        vec![Vision, FunctionCalling, JsonMode, MaxCompletionTokens],
+    );
+    capabilities.insert(
+        O3_MINI.to_owned(),
+        vec![FunctionCalling, JsonMode, MaxCompletionTokens],
+    );
+    for model in [O1_MINI, O1_PREVIEW] {
+        capabilities.insert(model.to_owned(), vec![MaxCompletionTokens]);
+    }
+    for model in [
+        TEXT_EMBEDDING_3_SMALL,
+        TEXT_EMBEDDING_3_LARGE,
+        TEXT_EMBEDDING_ADA_002,
+    ] {
+        capabilities.insert(model.to_owned(), vec![Embeddings]);
+    }
+    capabilities
+}
+
+/// Sort order of list endpoints, by `created_at`.
+#[derive(Debug, Serialize, Deserialize, Copy, Clone, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum SortOrder {
+    Asc,
+    Desc,
+}
+
+impl std::fmt::Display for SortOrder {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            SortOrder::Asc => write!(f, "asc"),
+            SortOrder::Desc => write!(f, "desc"),
+        }
+    }
+}
+
+/// The API accepts at most this many stop sequences per request.
+pub const MAX_STOP_SEQUENCES: usize = 4;
+
+/// Sequences at which the model stops generating, given as a single string or
+/// a list of up to `MAX_STOP_SEQUENCES`. The stop sequence itself is not part
+/// of the output.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(untagged)]
+pub enum StopSequence {
+    Single(String),
+    Multiple(Vec<String>),
+}
+
+impl StopSequence {
+    pub fn as_slice(&self) -> &[String] {
+        match self {
+            StopSequence::Single(stop) => std::slice::from_ref(stop),
+            StopSequence::Multiple(stops) => stops,
+        }
+    }
+}
+
+impl From<String> for StopSequence {
+    fn from(stop: String) -> Self {
+        StopSequence::Single(stop)
+    }
+}
+
+impl From<&str> for StopSequence {
+    fn from(stop: &str) -> Self {
+        StopSequence::Single(stop.to_owned())
+    }
+}
+
+impl From<Vec<String>> for StopSequence {
+    fn from(stops: Vec<String>) -> Self {
+        StopSequence::Multiple(stops)
+    }
+}
+
+/// One page of a cursor-paginated list response, see `Client::paginate`.
+pub trait ListPage {
+    type Item;
+
+    /// Splits the page into its items and the `after` cursor of the next page,
+    /// which is `None` once the listing is exhausted.
+    fn into_page(self) -> (Vec<Self::Item>, Option<String>);
+}
+
+/// Fills `{name}` placeholders in `template` from `vars`, for building system
+/// messages and assistant `instructions`. `{{` and `}}` produce literal braces.
+/// A placeholder without a matching variable, or an unbalanced brace, is an
+/// error rather than being left in the output.
+pub fn render_instructions(template: &str, vars: &HashMap<&str, &str>) -> Result<String, APIError> {
+    let mut rendered = String::with_capacity(template.len());
+    let mut chars = template.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '{' if chars.peek() == Some(&'{') => {
+                chars.next();
+                rendered.push('{');
+            }
+            '}' if chars.peek() == Some(&'}') => {
+                chars.next();
+                rendered.push('}');
+            }
+            '{' => {
+                let mut name = String::new();
+                loop {
+                    match chars.next() {
+                        Some('}') => break,
+                        Some(c) => name.push(c),
+                        None => {
+                            return Err(APIError::new(
+                                APIErrorKind::Other,
+                                format!("unclosed placeholder {{{} in template", name),
+                            ))
+                        }
+                    }
+                }
+                match vars.get(name.trim()) {
+                    Some(value) => rendered.push_str(value),
+                    None => {
+                        return Err(APIError::new(
+                            APIErrorKind::Other,
+                            format!("missing template variable {:?}", name.trim()),
+                        ))
+                    }
+                }
+            }
+            '}' => {
+                return Err(APIError::new(
+                    APIErrorKind::Other,
+                    "unmatched '}' in template, use '}}' for a literal brace",
+                ))
+            }
+            c => rendered.push(c),
+        }
+    }
+    Ok(rendered)
+}
+
+/// A response that carries the status code and headers of the HTTP response
+/// it was decoded from, filled in by `Client`.
+pub trait WithHeaders {
+    fn set_headers(&mut self, headers: HashMap<String, String>);
+    fn set_http_status(&mut self, status: u16);
+}
+
+/// Implements `WithHeaders` for response types with the usual `headers` and
+/// `http_status` fields.
+#[macro_export]
+macro_rules! impl_with_headers {
+    ($($response:ty),*) => {
+        $(
+            impl $crate::v1::common::WithHeaders for $response {
+                fn set_headers(&mut self, headers: ::std::collections::HashMap<String, String>) {
+                    self.headers = Some(headers);
+                }
+
+                fn set_http_status(&mut self, status: u16) {
+                    self.http_status = Some(status);
+                }
+            }
+        )*
+    };
 }
 
 #[macro_export]
@@ -20,11 +372,18 @@ macro_rules! impl_builder_methods {
         }
     };
 }
+
 // https://platform.openai.com/docs/models/gpt-4o
 pub const GPT4_O: &str = "gpt-4o";
 pub const GPT4_O_2024_05_13: &str = "gpt-4o-2024-05-13";
 pub const MISTRAL : &str = "mistral";
 
+// https://platform.openai.com/docs/models/o1
+pub const O1: &str = "o1";
+pub const O1_MINI: &str = "o1-mini";
+pub const O1_PREVIEW: &str = "o1-preview";
+pub const O3_MINI: &str = "o3-mini";
+
 // https://platform.openai.com/docs/models/gpt-3-5
 pub const GPT3_5_TURBO_1106: &str = "gpt-3.5-turbo-1106";
 pub const GPT3_5_TURBO: &str = "gpt-3.5-turbo";
@@ -56,3 +415,297 @@ pub const DALL_E_3: &str = "dall-e-3";
 pub const TEXT_EMBEDDING_3_SMALL: &str = "text-embedding-3-small";
 pub const TEXT_EMBEDDING_3_LARGE: &str = "text-embedding-3-large";
 pub const TEXT_EMBEDDING_ADA_002: &str = "text-embedding-ada-002";
+
+/// The models named by the constants above, so names are checked at compile
+/// time and can be enumerated through `Model::KNOWN`. `Custom` carries any
+/// other name a server accepts. Converts into the model's `String` name, and
+/// (de)serializes as it.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Deserialize, Serialize)]
+#[serde(from = "String", into = "String")]
+pub enum Model {
+    Gpt4O,
+    Gpt4O20240513,
+    Mistral,
+    O1,
+    O1Mini,
+    O1Preview,
+    O3Mini,
+    Gpt35Turbo1106,
+    Gpt35Turbo,
+    Gpt35Turbo16k,
+    Gpt35TurboInstruct,
+    Gpt35Turbo0613,
+    Gpt35Turbo16k0613,
+    Gpt35Turbo0301,
+    Gpt40125Preview,
+    Gpt4TurboPreview,
+    Gpt41106Preview,
+    Gpt4VisionPreview,
+    Gpt4,
+    Gpt432k,
+    Gpt40613,
+    Gpt432k0613,
+    Gpt40314,
+    Gpt432k0314,
+    DallE2,
+    DallE3,
+    TextEmbedding3Small,
+    TextEmbedding3Large,
+    TextEmbeddingAda002,
+    Custom(String),
+}
+
+impl Model {
+    /// Every variant but `Custom`.
+    pub const KNOWN: &'static [Model] = &[
+        Model::Gpt4O,
+        Model::Gpt4O20240513,
+        Model::Mistral,
+        Model::O1,
+        Model::O1Mini,
+        Model::O1Preview,
+        Model::O3Mini,
+        Model::Gpt35Turbo1106,
+        Model::Gpt35Turbo,
+        Model::Gpt35Turbo16k,
+        Model::Gpt35TurboInstruct,
+        Model::Gpt35Turbo0613,
+        Model::Gpt35Turbo16k0613,
+        Model::Gpt35Turbo0301,
+        Model::Gpt40125Preview,
+        Model::Gpt4TurboPreview,
+        Model::Gpt41106Preview,
+        Model::Gpt4VisionPreview,
+        Model::Gpt4,
+        Model::Gpt432k,
+        Model::Gpt40613,
+        Model::Gpt432k0613,
+        Model::Gpt40314,
+        Model::Gpt432k0314,
+        Model::DallE2,
+        Model::DallE3,
+        Model::TextEmbedding3Small,
+        Model::TextEmbedding3Large,
+        Model::TextEmbeddingAda002,
+    ];
+
+    pub fn as_str(&self) -> &str {
+        match self {
+            Model::Gpt4O => GPT4_O,
+            Model::Gpt4O20240513 => GPT4_O_2024_05_13,
+            Model::Mistral => MISTRAL,
+            Model::O1 => O1,
+            Model::O1Mini => O1_MINI,
+            Model::O1Preview => O1_PREVIEW,
+            Model::O3Mini => O3_MINI,
+            Model::Gpt35Turbo1106 => GPT3_5_TURBO_1106,
+            Model::Gpt35Turbo => GPT3_5_TURBO,
+            Model::Gpt35Turbo16k => GPT3_5_TURBO_16K,
+            Model::Gpt35TurboInstruct => GPT3_5_TURBO_INSTRUCT,
+            Model::Gpt35Turbo0613 => GPT3_5_TURBO_0613,
+            Model::Gpt35Turbo16k0613 => GPT3_5_TURBO_16K_0613,
+            Model::Gpt35Turbo0301 => GPT3_5_TURBO_0301,
+            Model::Gpt40125Preview => GPT4_0125_PREVIEW,
+            Model::Gpt4TurboPreview => GPT4_TURBO_PREVIEW,
+            Model::Gpt41106Preview => GPT4_1106_PREVIEW,
+            Model::Gpt4VisionPreview => GPT4_VISION_PREVIEW,
+            Model::Gpt4 => GPT4,
+            Model::Gpt432k => GPT4_32K,
+            Model::Gpt40613 => GPT4_0613,
+            Model::Gpt432k0613 => GPT4_32K_0613,
+            Model::Gpt40314 => GPT4_0314,
+            Model::Gpt432k0314 => GPT4_32K_0314,
+            Model::DallE2 => DALL_E_2,
+            Model::DallE3 => DALL_E_3,
+            Model::TextEmbedding3Small => TEXT_EMBEDDING_3_SMALL,
+            Model::TextEmbedding3Large => TEXT_EMBEDDING_3_LARGE,
+            Model::TextEmbeddingAda002 => TEXT_EMBEDDING_ADA_002,
+            Model::Custom(name) => name,
+        }
+    }
+}
+
+impl fmt::Display for Model {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Known names map to their variant, anything else to `Custom`.
+impl From<&str> for Model {
+    fn from(name: &str) -> Self {
+        Model::KNOWN
+            .iter()
+            .find(|model| model.as_str() == name)
+            .cloned()
+            .unwrap_or_else(|| Model::Custom(name.to_owned()))
+    }
+}
+
+impl From<String> for Model {
+    fn from(name: String) -> Self {
+        match Model::from(name.as_str()) {
+            Model::Custom(_) => Model::Custom(name),
+            model => model,
+        }
+    }
+}
+
+impl From<Model> for String {
+    fn from(model: Model) -> Self {
+        match model {
+            Model::Custom(name) => name,
+            model => model.as_str().to_owned(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn usage_accepts_openai_names() {
+        let usage: Usage = serde_json::from_value(json!({
+            "prompt_tokens": 10,
+            "completion_tokens": 4,
+            "total_tokens": 14,
+            "prompt_tokens_details": {"cached_tokens": 8}
+        }))
+        .unwrap();
+        assert_eq!(usage.prompt_tokens, 10);
+        assert_eq!(usage.completion_tokens, 4);
+        assert_eq!(usage.total_tokens, 14);
+        assert_eq!(usage.prompt_tokens_details.unwrap().cached_tokens, 8);
+    }
+
+    #[test]
+    fn usage_accepts_input_output_names_without_total() {
+        let usage: Usage = serde_json::from_value(json!({
+            "input_tokens": 10,
+            "output_tokens": 4
+        }))
+        .unwrap();
+        assert_eq!(usage.prompt_tokens, 10);
+        assert_eq!(usage.completion_tokens, 4);
+        assert_eq!(usage.total_tokens, 14);
+    }
+
+    #[test]
+    fn usage_keeps_a_reported_total() {
+        // Some servers count tokens outside prompt and completion, e.g. tool
+        // definitions, so the sum isn't forced.
+        let usage: Usage = serde_json::from_value(json!({
+            "input_tokens": 10,
+            "output_tokens": 4,
+            "total_tokens": 20
+        }))
+        .unwrap();
+        assert_eq!(usage.total_tokens, 20);
+    }
+
+    #[test]
+    fn embedding_usage_without_completion_tokens() {
+        let usage: Usage =
+            serde_json::from_value(json!({"prompt_tokens": 3, "total_tokens": 3})).unwrap();
+        assert_eq!(usage.completion_tokens, 0);
+    }
+
+    #[test]
+    fn usage_round_trips_under_openai_names() {
+        let usage = Usage {
+            prompt_tokens: 1,
+            completion_tokens: 2,
+            total_tokens: 3,
+            ..Default::default()
+        };
+        let value = serde_json::to_value(&usage).unwrap();
+        assert_eq!(
+            value,
+            json!({"prompt_tokens": 1, "completion_tokens": 2, "total_tokens": 3})
+        );
+        assert_eq!(serde_json::from_value::<Usage>(value).unwrap(), usage);
+    }
+
+    fn headers(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+        pairs
+            .iter()
+            .map(|(name, value)| (name.to_string(), value.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn rate_limit_parses_counts_and_resets() {
+        let rate_limit = RateLimit::from_headers(&headers(&[
+            ("x-ratelimit-limit-requests", "60"),
+            ("x-ratelimit-remaining-requests", "59"),
+            ("x-ratelimit-reset-requests", "1s"),
+            ("x-ratelimit-reset-tokens", "6m0.5s"),
+        ]))
+        .unwrap();
+        assert_eq!(rate_limit.limit_requests, Some(60));
+        assert_eq!(rate_limit.remaining_requests, Some(59));
+        assert_eq!(rate_limit.reset_requests, Some(Duration::from_secs(1)));
+        assert_eq!(
+            rate_limit.reset_tokens,
+            Some(Duration::from_millis(360_500))
+        );
+        assert_eq!(rate_limit.limit_tokens, None);
+        assert_eq!(RateLimit::from_headers(&headers(&[])), None);
+    }
+
+    #[test]
+    fn rate_limit_ignores_out_of_range_resets() {
+        let rate_limit = RateLimit::from_headers(&headers(&[
+            ("x-ratelimit-remaining-requests", "0"),
+            ("x-ratelimit-reset-requests", "99999999999999999999h"),
+        ]))
+        .unwrap();
+        assert_eq!(rate_limit.remaining_requests, Some(0));
+        assert_eq!(rate_limit.reset_requests, None);
+        assert_eq!(parse_reset("5x"), None);
+        assert_eq!(parse_reset("20ms"), Some(Duration::from_millis(20)));
+    }
+
+    fn render(template: &str) -> Result<String, APIError> {
+        let vars = HashMap::from([("name", "Ada"), ("lang", "Rust")]);
+        render_instructions(template, &vars)
+    }
+
+    #[test]
+    fn render_instructions_fills_placeholders() {
+        assert_eq!(
+            render("You help {name} write { lang }.").unwrap(),
+            "You help Ada write Rust."
+        );
+        assert_eq!(render("no placeholders").unwrap(), "no placeholders");
+    }
+
+    #[test]
+    fn render_instructions_unescapes_doubled_braces() {
+        assert_eq!(
+            render("Reply as {{\"user\": \"{name}\"}}").unwrap(),
+            "Reply as {\"user\": \"Ada\"}"
+        );
+    }
+
+    #[test]
+    fn render_instructions_rejects_missing_variables() {
+        let err = render("Hello {surname}").unwrap_err();
+        assert_eq!(err.kind, APIErrorKind::Other);
+        assert_eq!(err.message, "missing template variable \"surname\"");
+    }
+
+    #[test]
+    fn render_instructions_rejects_unbalanced_braces() {
+        assert_eq!(
+            render("Hello {name").unwrap_err().message,
+            "unclosed placeholder {name in template"
+        );
+        assert!(render("Hello name}")
+            .unwrap_err()
+            .message
+            .starts_with("unmatched '}'"));
+    }
+}
@@ -3,6 +3,7 @@ use std::collections::HashMap;
 use std::option::Option;
 
 use crate::impl_builder_methods;
+use crate::impl_with_headers;
 use crate::v1::common;
 
 pub const GPT3_TEXT_DAVINCI_003: &str = "text-davinci-003";
@@ -39,7 +40,7 @@ pub struct CompletionRequest {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub echo: Option<bool>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub stop: Option<Vec<String>>,
+    pub stop: Option<common::StopSequence>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub presence_penalty: Option<f32>,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -53,9 +54,9 @@ pub struct CompletionRequest {
 }
 
 impl CompletionRequest {
-    pub fn new(model: String, prompt: String) -> Self {
+    pub fn new(model: impl Into<String>, prompt: String) -> Self {
         Self {
-            model,
+            model: model.into(),
             prompt,
             suffix: None,
             max_tokens: None,
@@ -73,6 +74,12 @@ impl CompletionRequest {
             user: None,
         }
     }
+
+    /// Accepts a single sequence or a `Vec` of them.
+    pub fn stop(mut self, stop: impl Into<common::StopSequence>) -> Self {
+        self.stop = Some(stop.into());
+        self
+    }
 }
 
 impl_builder_methods!(
@@ -85,7 +92,6 @@ impl_builder_methods!(
     stream: bool,
     logprobs: i32,
     echo: bool,
-    stop: Vec<String>,
     presence_penalty: f32,
     frequency_penalty: f32,
     best_of: i32,
@@ -113,9 +119,61 @@ pub struct LogprobResult {
 pub struct CompletionResponse {
     pub id: String,
     pub object: String,
+    #[serde(alias = "created_at")]
     pub created: i64,
     pub model: String,
     pub choices: Vec<CompletionChoice>,
     pub usage: common::Usage,
     pub headers: Option<HashMap<String, String>>,
+    pub http_status: Option<u16>,
+}
+
+impl_with_headers!(CompletionResponse);
+
+impl CompletionResponse {
+    /// Rate-limit budget reported alongside this response.
+    pub fn rate_limit(&self) -> Option<common::RateLimit> {
+        common::RateLimit::from_headers(self.headers.as_ref()?)
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct CompletionChunkChoice {
+    pub text: String,
+    pub index: i64,
+    pub finish_reason: Option<String>,
+    pub logprobs: Option<LogprobResult>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct CompletionChunkResponse {
+    pub id: String,
+    pub object: String,
+    #[serde(alias = "created_at")]
+    pub created: i64,
+    pub model: String,
+    pub choices: Vec<CompletionChunkChoice>,
+}
+
+impl From<CompletionResponse> for CompletionChunkResponse {
+    /// Presents a complete response as a single chunk carrying every choice
+    /// in full.
+    fn from(res: CompletionResponse) -> Self {
+        Self {
+            id: res.id,
+            object: res.object,
+            created: res.created,
+            model: res.model,
+            choices: res
+                .choices
+                .into_iter()
+                .map(|c| CompletionChunkChoice {
+                    text: c.text,
+                    index: c.index,
+                    finish_reason: Some(c.finish_reason),
+                    logprobs: c.logprobs,
+                })
+                .collect(),
+        }
+    }
 }
@@ -3,6 +3,7 @@ use std::collections::HashMap;
 use std::option::Option;
 
 use crate::impl_builder_methods;
+use crate::impl_with_headers;
 use crate::v1::common;
 
 #[derive(Debug, Serialize, Clone)]
@@ -49,8 +50,12 @@ pub struct EditChoice {
 #[derive(Debug, Deserialize, Serialize)]
 pub struct EditResponse {
     pub object: String,
+    #[serde(alias = "created_at")]
     pub created: i64,
     pub usage: common::Usage,
     pub choices: Vec<EditChoice>,
     pub headers: Option<HashMap<String, String>>,
+    pub http_status: Option<u16>,
 }
+
+impl_with_headers!(EditResponse);
@@ -1,32 +1,112 @@
-use serde::{Deserialize, Serialize};
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use serde::{Deserialize, Deserializer, Serialize};
 use std::collections::HashMap;
 use std::option::Option;
 
 use crate::impl_builder_methods;
+use crate::impl_with_headers;
+use crate::v1::common;
 
 #[derive(Debug, Deserialize, Serialize)]
 pub struct EmbeddingData {
     pub object: String,
+    /// Always decoded to floats, whichever `encoding_format` was requested.
+    #[serde(deserialize_with = "deserialize_embedding")]
     pub embedding: Vec<f32>,
     pub index: i32,
 }
 
+/// Wire format of the embedding vectors. `Base64` packs each vector as
+/// little-endian `f32`s, which is about a quarter of the size of the JSON
+/// float array for large batches.
+#[derive(Debug, Serialize, Deserialize, Copy, Clone, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum EmbeddingEncodingFormat {
+    Float,
+    Base64,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum EncodedEmbedding {
+    Float(Vec<f32>),
+    Base64(String),
+}
+
+fn deserialize_embedding<'de, D>(deserializer: D) -> Result<Vec<f32>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    match EncodedEmbedding::deserialize(deserializer)? {
+        EncodedEmbedding::Float(embedding) => Ok(embedding),
+        EncodedEmbedding::Base64(encoded) => {
+            let bytes = STANDARD.decode(encoded).map_err(serde::de::Error::custom)?;
+            if bytes.len() % 4 != 0 {
+                return Err(serde::de::Error::custom(format!(
+                    "base64 embedding of {} bytes is not a sequence of f32",
+                    bytes.len()
+                )));
+            }
+            Ok(bytes
+                .chunks_exact(4)
+                .map(|b| f32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+                .collect())
+        }
+    }
+}
+
+/// What to embed. Each text or token sequence gets its own entry in
+/// `EmbeddingResponse.data`, whose `index` points back into the input.
+#[derive(Debug, Serialize, Clone, PartialEq, Eq)]
+#[serde(untagged)]
+pub enum EmbeddingInput {
+    Text(String),
+    Texts(Vec<String>),
+    Tokens(Vec<u32>),
+    TokenBatches(Vec<Vec<u32>>),
+}
+
+impl From<String> for EmbeddingInput {
+    fn from(text: String) -> Self {
+        EmbeddingInput::Text(text)
+    }
+}
+
+impl From<Vec<String>> for EmbeddingInput {
+    fn from(texts: Vec<String>) -> Self {
+        EmbeddingInput::Texts(texts)
+    }
+}
+
 #[derive(Debug, Serialize, Clone)]
 pub struct EmbeddingRequest {
     pub model: String,
-    pub input: String,
+    pub input: EmbeddingInput,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub dimensions: Option<i32>,
     #[serde(skip_serializing_if = "Option::is_none")]
+    pub encoding_format: Option<EmbeddingEncodingFormat>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub user: Option<String>,
 }
 
 impl EmbeddingRequest {
-    pub fn new(model: String, input: String) -> Self {
+    pub fn new(model: impl Into<String>, input: String) -> Self {
+        Self::new_with_input(model, EmbeddingInput::Text(input))
+    }
+
+    /// Embeds all `inputs` in one call.
+    pub fn new_batch(model: impl Into<String>, inputs: Vec<String>) -> Self {
+        Self::new_with_input(model, EmbeddingInput::Texts(inputs))
+    }
+
+    pub fn new_with_input(model: impl Into<String>, input: EmbeddingInput) -> Self {
         Self {
-            model,
+            model: model.into(),
             input,
             dimensions: None,
+            encoding_format: None,
             user: None,
         }
     }
@@ -34,6 +114,7 @@ impl EmbeddingRequest {
 
 impl_builder_methods!(
     EmbeddingRequest,
+    encoding_format: EmbeddingEncodingFormat,
     user: String
 );
 
@@ -42,12 +123,64 @@ pub struct EmbeddingResponse {
     pub object: String,
     pub data: Vec<EmbeddingData>,
     pub model: String,
-    pub usage: Usage,
+    pub usage: common::Usage,
     pub headers: Option<HashMap<String, String>>,
+    pub http_status: Option<u16>,
 }
 
-#[derive(Debug, Deserialize, Serialize)]
-pub struct Usage {
-    pub prompt_tokens: i32,
-    pub total_tokens: i32,
+impl_with_headers!(EmbeddingResponse);
+
+impl EmbeddingResponse {
+    /// The embeddings ordered like the request's inputs, whatever order the
+    /// server listed them in.
+    pub fn into_embeddings(self) -> Vec<Vec<f32>> {
+        let mut data = self.data;
+        data.sort_by_key(|d| d.index);
+        data.into_iter().map(|d| d.embedding).collect()
+    }
+}
+
+#[deprecated(note = "embedding responses now use `common::Usage`")]
+pub type Usage = common::Usage;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn data(embedding: serde_json::Value) -> Result<EmbeddingData, serde_json::Error> {
+        serde_json::from_value(json!({"object": "embedding", "embedding": embedding, "index": 0}))
+    }
+
+    #[test]
+    fn base64_embedding_round_trips_to_the_same_floats() {
+        let floats = vec![0.5f32, -1.25, 3.0e-7, f32::MAX];
+        let bytes: Vec<u8> = floats.iter().flat_map(|f| f.to_le_bytes()).collect();
+        let decoded = data(json!(STANDARD.encode(bytes))).unwrap();
+        assert_eq!(decoded.embedding, floats);
+        assert_eq!(data(json!(floats)).unwrap().embedding, floats);
+    }
+
+    #[test]
+    fn base64_embedding_of_partial_floats_is_rejected() {
+        let err = data(json!(STANDARD.encode([0u8, 0, 128]))).unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("base64 embedding of 3 bytes is not a sequence of f32"));
+        assert!(data(json!("not base64!")).is_err());
+    }
+
+    #[test]
+    fn encoding_format_is_sent_lowercase_and_only_when_set() {
+        let req = EmbeddingRequest::new("nomic-embed-text".to_owned(), "Hello".to_owned());
+        assert!(serde_json::to_value(&req)
+            .unwrap()
+            .get("encoding_format")
+            .is_none());
+        let req = req.encoding_format(EmbeddingEncodingFormat::Base64);
+        assert_eq!(
+            serde_json::to_value(&req).unwrap()["encoding_format"],
+            "base64"
+        );
+    }
 }
@@ -1,15 +1,181 @@
 use std::error::Error;
 use std::fmt;
+use std::sync::Arc;
 
-#[derive(Debug)]
+use crate::v1::run::RunStatus;
+
+#[derive(Debug, Clone)]
+pub enum APIErrorKind {
+    /// The server answered with a non-success status code.
+    Http { status: u16, body: String },
+    /// The response body did not match the expected type. `raw_body` holds what
+    /// the server actually sent, truncated to a readable length. `source` is
+    /// the deserializer's error, absent when the body couldn't be read at all;
+    /// it is also what `APIError::source` returns.
+    Decode {
+        source: Option<Arc<serde_json::Error>>,
+        raw_body: String,
+    },
+    /// The request failed before a response arrived (connect, DNS, TLS, ...).
+    Network,
+    /// The request or the body read timed out.
+    Timeout,
+    /// A streaming response broke off or carried an unreadable event.
+    Stream,
+    /// The model's structured output (`response_format` with a JSON schema)
+    /// didn't deserialize into the requested type. `raw_content` holds the
+    /// message content as returned, truncated to a readable length.
+    StructuredOutput { raw_content: String },
+    /// An assistant run ended in `failed`, `cancelled`, `expired` or
+    /// `incomplete`. `last_error` is the server's explanation, if any.
+    Run {
+        status: RunStatus,
+        last_error: Option<String>,
+    },
+    /// Anything else, e.g. local I/O or invalid configuration.
+    Other,
+}
+
+// `serde_json::Error` isn't comparable, so decode errors compare by message.
+impl PartialEq for APIErrorKind {
+    fn eq(&self, other: &Self) -> bool {
+        use APIErrorKind::*;
+        match (self, other) {
+            (Http { status: a, body: x }, Http { status: b, body: y }) => a == b && x == y,
+            (
+                Decode {
+                    source: a,
+                    raw_body: x,
+                },
+                Decode {
+                    source: b,
+                    raw_body: y,
+                },
+            ) => {
+                a.as_ref().map(ToString::to_string) == b.as_ref().map(ToString::to_string) && x == y
+            }
+            (StructuredOutput { raw_content: a }, StructuredOutput { raw_content: b }) => a == b,
+            (
+                Run {
+                    status: a,
+                    last_error: x,
+                },
+                Run {
+                    status: b,
+                    last_error: y,
+                },
+            ) => a == b && x == y,
+            (Network, Network) | (Timeout, Timeout) | (Stream, Stream) | (Other, Other) => true,
+            _ => false,
+        }
+    }
+}
+
+impl Eq for APIErrorKind {}
+
+#[derive(Debug, Clone)]
 pub struct APIError {
+    pub kind: APIErrorKind,
     pub message: String,
 }
 
+impl APIError {
+    pub fn new(kind: APIErrorKind, message: impl Into<String>) -> Self {
+        Self {
+            kind,
+            message: message.into(),
+        }
+    }
+
+    /// HTTP status of the failed response, if the server answered at all.
+    pub fn status(&self) -> Option<u16> {
+        match self.kind {
+            APIErrorKind::Http { status, .. } => Some(status),
+            _ => None,
+        }
+    }
+}
+
+// Enough to recognise an HTML error page or a misshapen payload without
+// dragging a multi-megabyte body around in every error.
+const MAX_RAW_BODY_LEN: usize = 2048;
+
+/// Truncates `body` to at most `MAX_RAW_BODY_LEN` bytes on a char boundary.
+pub(crate) fn truncate_body(body: &str) -> String {
+    if body.len() <= MAX_RAW_BODY_LEN {
+        return body.to_owned();
+    }
+    let mut end = MAX_RAW_BODY_LEN;
+    while !body.is_char_boundary(end) {
+        end -= 1;
+    }
+    format!("{}... ({} bytes total)", &body[..end], body.len())
+}
+
 impl fmt::Display for APIError {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         write!(f, "APIError: {}", self.message)
     }
 }
 
-impl Error for APIError {}
+impl Error for APIError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match &self.kind {
+            APIErrorKind::Decode {
+                source: Some(source),
+                ..
+            } => Some(source.as_ref()),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn decode_error(body: &str) -> APIError {
+        let err = serde_json::from_str::<Vec<u8>>(body).unwrap_err();
+        APIError::new(
+            APIErrorKind::Decode {
+                source: Some(Arc::new(err)),
+                raw_body: body.to_owned(),
+            },
+            "decode failed",
+        )
+    }
+
+    #[test]
+    fn decode_error_exposes_the_serde_error_as_source() {
+        let err = decode_error("<html>");
+        let source = err.source().unwrap();
+        assert!(source
+            .downcast_ref::<serde_json::Error>()
+            .unwrap()
+            .is_syntax());
+        assert!(APIError::new(APIErrorKind::Network, "down")
+            .source()
+            .is_none());
+    }
+
+    #[test]
+    fn decode_errors_compare_by_message_and_body() {
+        assert_eq!(decode_error("<html>").kind, decode_error("<html>").kind);
+        assert_ne!(decode_error("<html>").kind, decode_error("{}").kind);
+        assert_ne!(
+            decode_error("<html>").kind,
+            APIErrorKind::Decode {
+                source: None,
+                raw_body: "<html>".to_owned(),
+            }
+        );
+    }
+
+    #[test]
+    fn truncate_body_cuts_on_a_char_boundary() {
+        let body = "é".repeat(MAX_RAW_BODY_LEN);
+        let truncated = truncate_body(&body);
+        assert!(truncated.ends_with(&format!("... ({} bytes total)", body.len())));
+        assert_eq!(truncate_body("short"), "short");
+    }
+}
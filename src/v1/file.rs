@@ -2,11 +2,14 @@ use std::collections::HashMap;
 
 use serde::{Deserialize, Serialize};
 
+use crate::impl_with_headers;
+
 #[derive(Debug, Deserialize, Serialize)]
 pub struct FileData {
     pub id: String,
+    #[serde(alias = "object")]
     pub oejct: String,
-    pub bytes: i32,
+    pub bytes: i64,
     pub created_at: i64,
     pub filename: String,
     pub purpose: String,
@@ -17,8 +20,11 @@ pub struct FileListResponse {
     pub object: String,
     pub data: Vec<FileData>,
     pub headers: Option<HashMap<String, String>>,
+    pub http_status: Option<u16>,
 }
 
+impl_with_headers!(FileListResponse);
+
 #[derive(Debug, Serialize)]
 pub struct FileUploadRequest {
     pub file: String,
@@ -40,8 +46,11 @@ pub struct FileUploadResponse {
     pub filename: String,
     pub purpose: String,
     pub headers: Option<HashMap<String, String>>,
+    pub http_status: Option<u16>,
 }
 
+impl_with_headers!(FileUploadResponse);
+
 #[derive(Debug, Serialize)]
 pub struct FileDeleteRequest {
     pub file_id: String,
@@ -59,8 +68,11 @@ pub struct FileDeleteResponse {
     pub oejct: String,
     pub delete: bool,
     pub headers: Option<HashMap<String, String>>,
+    pub http_status: Option<u16>,
 }
 
+impl_with_headers!(FileDeleteResponse);
+
 #[derive(Debug, Serialize)]
 pub struct FileRetrieveRequest {
     pub file_id: String,
@@ -81,8 +93,11 @@ pub struct FileRetrieveResponse {
     pub filename: String,
     pub purpose: String,
     pub headers: Option<HashMap<String, String>>,
+    pub http_status: Option<u16>,
 }
 
+impl_with_headers!(FileRetrieveResponse);
+
 #[derive(Debug, Serialize)]
 pub struct FileRetrieveContentRequest {
     pub file_id: String,
@@ -103,4 +118,69 @@ pub struct FileRetrieveContentResponse {
     pub filename: String,
     pub purpose: String,
     pub headers: Option<HashMap<String, String>>,
+    pub http_status: Option<u16>,
+}
+
+impl_with_headers!(FileRetrieveContentResponse);
+
+/// Opens an upload session that the file is then sent to in parts.
+#[derive(Debug, Serialize)]
+pub(crate) struct CreateUploadRequest {
+    pub filename: String,
+    pub purpose: String,
+    pub bytes: u64,
+    pub mime_type: String,
+}
+
+#[derive(Debug, Serialize)]
+pub(crate) struct CompleteUploadRequest {
+    pub part_ids: Vec<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct UploadObject {
+    pub id: String,
+    pub object: String,
+    pub bytes: u64,
+    pub created_at: i64,
+    pub filename: String,
+    pub purpose: String,
+    /// `pending`, `completed`, `cancelled` or `expired`.
+    pub status: String,
+    pub expires_at: Option<i64>,
+    /// The created file, once the upload is completed.
+    pub file: Option<FileData>,
+    pub headers: Option<HashMap<String, String>>,
+    pub http_status: Option<u16>,
+}
+
+impl_with_headers!(UploadObject);
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct UploadPartObject {
+    pub id: String,
+    pub object: String,
+    pub created_at: i64,
+    pub upload_id: String,
+    pub headers: Option<HashMap<String, String>>,
+    pub http_status: Option<u16>,
+}
+
+impl_with_headers!(UploadPartObject);
+
+/// How `Client::file_upload_resumable` sends the file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UploadMode {
+    /// Through an upload session, in parts that are retried one by one.
+    Parts,
+    /// In a single request to `/files`, retried as a whole, for backends
+    /// without upload sessions.
+    WholeFile,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct UploadProgress {
+    pub mode: UploadMode,
+    pub bytes_sent: u64,
+    pub total_bytes: u64,
 }
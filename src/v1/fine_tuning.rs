@@ -2,6 +2,8 @@ use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
 use crate::impl_builder_methods;
+use crate::impl_with_headers;
+use crate::v1::common::{ListPage, WithHeaders};
 
 #[derive(Debug, Serialize, Clone)]
 pub struct CreateFineTuningJobRequest {
@@ -100,6 +102,30 @@ pub struct FineTuningPagination<T> {
     pub data: Vec<T>,
     pub has_more: bool,
     pub headers: Option<HashMap<String, String>>,
+    pub http_status: Option<u16>,
+}
+
+impl<T> WithHeaders for FineTuningPagination<T> {
+    fn set_headers(&mut self, headers: HashMap<String, String>) {
+        self.headers = Some(headers);
+    }
+
+    fn set_http_status(&mut self, status: u16) {
+        self.http_status = Some(status);
+    }
+}
+
+// Fine-tuning listings have no `last_id`; the cursor is the id of the last item.
+impl ListPage for FineTuningPagination<FineTuningJobObject> {
+    type Item = FineTuningJobObject;
+
+    fn into_page(self) -> (Vec<FineTuningJobObject>, Option<String>) {
+        let next = match (self.has_more, self.data.last()) {
+            (true, Some(last)) => Some(last.id.clone()),
+            _ => None,
+        };
+        (self.data, next)
+    }
 }
 
 #[derive(Debug, Deserialize, Serialize)]
@@ -119,8 +145,11 @@ pub struct FineTuningJobObject {
     pub training_file: String,
     pub validation_file: Option<String>,
     pub headers: Option<HashMap<String, String>>,
+    pub http_status: Option<u16>,
 }
 
+impl_with_headers!(FineTuningJobObject);
+
 #[derive(Debug, Deserialize, Serialize)]
 pub struct FineTuningJobError {
     pub code: String,
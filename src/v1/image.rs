@@ -3,6 +3,7 @@ use std::collections::HashMap;
 use std::option::Option;
 
 use crate::impl_builder_methods;
+use crate::impl_with_headers;
 
 #[derive(Debug, Deserialize, Serialize)]
 pub struct ImageData {
@@ -48,11 +49,15 @@ impl_builder_methods!(
 
 #[derive(Debug, Deserialize, Serialize)]
 pub struct ImageGenerationResponse {
+    #[serde(alias = "created_at")]
     pub created: i64,
     pub data: Vec<ImageData>,
     pub headers: Option<HashMap<String, String>>,
+    pub http_status: Option<u16>,
 }
 
+impl_with_headers!(ImageGenerationResponse);
+
 #[derive(Debug, Serialize, Clone)]
 pub struct ImageEditRequest {
     pub image: String,
@@ -98,11 +103,15 @@ impl_builder_methods!(
 
 #[derive(Debug, Deserialize, Serialize)]
 pub struct ImageEditResponse {
+    #[serde(alias = "created_at")]
     pub created: i64,
     pub data: Vec<ImageData>,
     pub headers: Option<HashMap<String, String>>,
+    pub http_status: Option<u16>,
 }
 
+impl_with_headers!(ImageEditResponse);
+
 #[derive(Debug, Serialize, Clone)]
 pub struct ImageVariationRequest {
     pub image: String,
@@ -142,7 +151,11 @@ impl_builder_methods!(
 
 #[derive(Debug, Deserialize, Serialize)]
 pub struct ImageVariationResponse {
+    #[serde(alias = "created_at")]
     pub created: i64,
     pub data: Vec<ImageData>,
     pub headers: Option<HashMap<String, String>>,
+    pub http_status: Option<u16>,
 }
+
+impl_with_headers!(ImageVariationResponse);
@@ -2,6 +2,8 @@ use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
 use crate::impl_builder_methods;
+use crate::impl_with_headers;
+use crate::v1::common::ListPage;
 
 #[derive(Debug, Serialize, Clone)]
 pub struct CreateMessageRequest {
@@ -68,8 +70,11 @@ pub struct MessageObject {
     pub file_ids: Vec<String>,
     pub metadata: HashMap<String, String>,
     pub headers: Option<HashMap<String, String>>,
+    pub http_status: Option<u16>,
 }
 
+impl_with_headers!(MessageObject);
+
 #[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq)]
 #[allow(non_camel_case_types)]
 pub enum MessageRole {
@@ -92,6 +97,40 @@ pub struct ContentText {
     pub annotations: Vec<String>,
 }
 
+/// Incremental change to a message, sent as `thread.message.delta` while a
+/// run streams.
+#[derive(Debug, Deserialize, Serialize)]
+pub struct MessageDeltaObject {
+    pub id: String,
+    pub object: String,
+    pub delta: MessageDelta,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct MessageDelta {
+    /// Only sent on the first delta of a message.
+    pub role: Option<MessageRole>,
+    #[serde(default)]
+    pub content: Vec<MessageDeltaContent>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct MessageDeltaContent {
+    /// Position of the content part this delta extends.
+    pub index: usize,
+    #[serde(rename = "type")]
+    pub content_type: String,
+    pub text: Option<MessageDeltaText>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct MessageDeltaText {
+    /// Text to append to the content part.
+    pub value: Option<String>,
+    #[serde(default)]
+    pub annotations: Vec<serde_json::Value>,
+}
+
 #[derive(Debug, Deserialize, Serialize)]
 pub struct ListMessage {
     pub object: String,
@@ -100,6 +139,18 @@ pub struct ListMessage {
     pub last_id: String,
     pub has_more: bool,
     pub headers: Option<HashMap<String, String>>,
+    pub http_status: Option<u16>,
+}
+
+impl_with_headers!(ListMessage);
+
+impl ListPage for ListMessage {
+    type Item = MessageObject;
+
+    fn into_page(self) -> (Vec<MessageObject>, Option<String>) {
+        let next = self.has_more.then_some(self.last_id);
+        (self.data, next)
+    }
 }
 
 #[derive(Debug, Deserialize, Serialize)]
@@ -109,8 +160,11 @@ pub struct MessageFileObject {
     pub created_at: i64,
     pub message_id: String,
     pub headers: Option<HashMap<String, String>>,
+    pub http_status: Option<u16>,
 }
 
+impl_with_headers!(MessageFileObject);
+
 #[derive(Debug, Deserialize, Serialize)]
 pub struct ListMessageFile {
     pub object: String,
@@ -119,4 +173,16 @@ pub struct ListMessageFile {
     pub last_id: String,
     pub has_more: bool,
     pub headers: Option<HashMap<String, String>>,
+    pub http_status: Option<u16>,
+}
+
+impl_with_headers!(ListMessageFile);
+
+impl ListPage for ListMessageFile {
+    type Item = MessageFileObject;
+
+    fn into_page(self) -> (Vec<MessageFileObject>, Option<String>) {
+        let next = self.has_more.then_some(self.last_id);
+        (self.data, next)
+    }
 }
@@ -0,0 +1,203 @@
+//! Canned responses for testing code that uses `Client` without a server,
+//! available with the `test-util` feature.
+//!
+//! Register responses on a `MockTransport`, hand a clone of it to
+//! `ClientBuilder::transport`, and inspect what the client sent through
+//! `MockTransport::requests`.
+
+use futures::future::BoxFuture;
+use futures::FutureExt;
+use reqwest::header::HeaderMap;
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use crate::v1::api::HttpTransport;
+use crate::v1::error::{APIError, APIErrorKind};
+
+/// A response to serve, e.g. a recorded fixture.
+#[derive(Debug, Clone)]
+pub struct MockResponse {
+    pub status: u16,
+    pub body: String,
+    pub headers: Vec<(String, String)>,
+    /// How long the response takes to arrive.
+    pub delay: Duration,
+}
+
+impl MockResponse {
+    pub fn new(status: u16, body: impl Into<String>) -> Self {
+        Self {
+            status,
+            body: body.into(),
+            headers: Vec::new(),
+            delay: Duration::ZERO,
+        }
+    }
+
+    /// A `200` with `body` as JSON.
+    pub fn json(body: &serde_json::Value) -> Self {
+        Self::new(200, body.to_string()).header("content-type", "application/json")
+    }
+
+    /// A `200` streaming `events` as `data:` lines followed by `data: [DONE]`,
+    /// like a streamed completion.
+    pub fn sse(events: &[serde_json::Value]) -> Self {
+        let mut body = String::new();
+        for event in events {
+            body.push_str(&format!("data: {}\n\n", event));
+        }
+        body.push_str("data: [DONE]\n\n");
+        Self::new(200, body).header("content-type", "text/event-stream")
+    }
+
+    pub fn header(mut self, name: &str, value: &str) -> Self {
+        self.headers.push((name.to_owned(), value.to_owned()));
+        self
+    }
+
+    /// Holds the response back for `delay`, e.g. to keep a request in flight
+    /// while others are sent.
+    pub fn delay(mut self, delay: Duration) -> Self {
+        self.delay = delay;
+        self
+    }
+}
+
+/// A request the client sent through a `MockTransport`.
+#[derive(Debug, Clone)]
+pub struct MockRequest {
+    pub method: String,
+    /// The URL path, including the endpoint's own path such as `/v1`.
+    pub path: String,
+    pub query: Option<String>,
+    /// The headers as sent, including `Authorization` and any added by a
+    /// request signer.
+    pub headers: HeaderMap,
+    pub body: Vec<u8>,
+}
+
+impl MockRequest {
+    /// The value of header `name`, `None` if it's missing or not UTF-8.
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers.get(name).and_then(|value| value.to_str().ok())
+    }
+
+    /// The body parsed as JSON, `None` if it isn't JSON.
+    pub fn json(&self) -> Option<serde_json::Value> {
+        serde_json::from_slice(&self.body).ok()
+    }
+}
+
+struct Route {
+    method: String,
+    path: String,
+    responses: VecDeque<MockResponse>,
+}
+
+#[derive(Default)]
+struct MockState {
+    routes: Vec<Route>,
+    requests: Vec<MockRequest>,
+}
+
+/// An `HttpTransport` answering from registered responses. Clones share the
+/// same routes and recorded requests.
+///
+/// A route matches requests with its method whose URL path ends with its
+/// path, so `/chat/completions` matches whatever the endpoint's prefix is;
+/// routes are tried in registration order. Registering a route again queues
+/// another response: each is served once, in order, and the last one answers
+/// every request after that. Requests matching no route get a `404`.
+#[derive(Clone, Default)]
+pub struct MockTransport {
+    state: Arc<Mutex<MockState>>,
+}
+
+impl MockTransport {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Answers `method` requests to `path` with `response`.
+    pub fn on(self, method: &str, path: &str, response: MockResponse) -> Self {
+        {
+            let mut state = self.state.lock().unwrap();
+            let method = method.to_ascii_uppercase();
+            match state
+                .routes
+                .iter_mut()
+                .find(|route| route.method == method && route.path == path)
+            {
+                Some(route) => route.responses.push_back(response),
+                None => state.routes.push(Route {
+                    method,
+                    path: path.to_owned(),
+                    responses: VecDeque::from([response]),
+                }),
+            }
+        }
+        self
+    }
+
+    /// Every request sent so far, oldest first.
+    pub fn requests(&self) -> Vec<MockRequest> {
+        self.state.lock().unwrap().requests.clone()
+    }
+
+    fn respond(&self, request: &reqwest::Request) -> MockResponse {
+        let mut state = self.state.lock().unwrap();
+        let method = request.method().as_str();
+        let path = request.url().path();
+        state.requests.push(MockRequest {
+            method: method.to_owned(),
+            path: path.to_owned(),
+            query: request.url().query().map(str::to_owned),
+            headers: request.headers().clone(),
+            body: request
+                .body()
+                .and_then(|body| body.as_bytes())
+                .map(<[u8]>::to_vec)
+                .unwrap_or_default(),
+        });
+        let route = state
+            .routes
+            .iter_mut()
+            .find(|route| route.method == method && path.ends_with(&route.path));
+        match route {
+            Some(route) if route.responses.len() > 1 => route.responses.pop_front().unwrap(),
+            Some(route) => route.responses[0].clone(),
+            None => MockResponse::new(
+                404,
+                format!("no mock response registered for {} {}", method, path),
+            ),
+        }
+    }
+}
+
+impl HttpTransport for MockTransport {
+    fn execute(
+        &self,
+        request: reqwest::Request,
+    ) -> BoxFuture<'static, Result<reqwest::Response, APIError>> {
+        let mock = self.respond(&request);
+        let mut response = http::Response::builder().status(mock.status);
+        for (name, value) in &mock.headers {
+            response = response.header(name, value);
+        }
+        let result = response
+            .body(mock.body)
+            .map(reqwest::Response::from)
+            .map_err(|e| {
+                APIError::new(APIErrorKind::Other, format!("invalid mock response: {}", e))
+            });
+        let delay = mock.delay;
+        async move {
+            if !delay.is_zero() {
+                tokio::time::sleep(delay).await;
+            }
+            result
+        }
+        .boxed()
+    }
+}
@@ -9,12 +9,20 @@ pub mod embedding;
 pub mod file;
 pub mod fine_tuning;
 pub mod image;
+pub mod model;
 pub mod moderation;
+pub mod sse;
+pub mod usage;
 
 // beta
 pub mod assistant;
 pub mod message;
+pub mod responses;
 pub mod run;
 pub mod thread;
 
 pub mod api;
+#[cfg(feature = "blocking")]
+pub mod blocking;
+#[cfg(any(test, feature = "test-util"))]
+pub mod mock;
@@ -0,0 +1,31 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use crate::impl_with_headers;
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct ModelObject {
+    pub id: String,
+    pub object: String,
+    pub created: Option<i64>,
+    pub owned_by: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct ListModels {
+    pub object: String,
+    pub data: Vec<ModelObject>,
+    pub headers: Option<HashMap<String, String>>,
+    pub http_status: Option<u16>,
+}
+
+impl_with_headers!(ListModels);
+
+/// Result of `Client::models_supporting`.
+#[derive(Debug)]
+pub struct SupportedModels {
+    pub models: Vec<ModelObject>,
+    /// `false` when none of the backend's models appear in the capability map.
+    /// `models` then holds every listed model, unfiltered.
+    pub capabilities_known: bool,
+}
@@ -2,6 +2,7 @@ use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
 use crate::impl_builder_methods;
+use crate::impl_with_headers;
 
 #[derive(Debug, Serialize, Clone)]
 pub struct CreateModerationRequest {
@@ -27,43 +28,93 @@ pub struct CreateModerationResponse {
     pub model: String,
     pub results: Vec<ModerationResult>,
     pub headers: Option<HashMap<String, String>>,
+    pub http_status: Option<u16>,
 }
 
-#[derive(Debug, Deserialize, Serialize)]
+impl_with_headers!(CreateModerationResponse);
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct ModerationResult {
     pub categories: ModerationCategories,
     pub category_scores: ModerationCategoryScores,
     pub flagged: bool,
 }
 
-#[derive(Debug, Deserialize, Serialize)]
+impl ModerationResult {
+    /// Shorthand for `categories.flagged_categories()`.
+    pub fn flagged_categories(&self) -> Vec<&'static str> {
+        self.categories.flagged_categories()
+    }
+}
+
+/// Which policies an input violates, by category. Categories older servers
+/// don't report read as `false`.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Deserialize, Serialize)]
 pub struct ModerationCategories {
-    #[serde(rename = "hate")]
-    pub is_hate: bool,
+    pub hate: bool,
     #[serde(rename = "hate/threatening")]
-    pub is_hate_threatening: bool,
+    pub hate_threatening: bool,
+    #[serde(default)]
+    pub harassment: bool,
+    #[serde(default, rename = "harassment/threatening")]
+    pub harassment_threatening: bool,
     #[serde(rename = "self-harm")]
-    pub is_self_harm: bool,
+    pub self_harm: bool,
+    #[serde(default, rename = "self-harm/intent")]
+    pub self_harm_intent: bool,
+    #[serde(default, rename = "self-harm/instructions")]
+    pub self_harm_instructions: bool,
     pub sexual: bool,
     #[serde(rename = "sexual/minors")]
-    pub is_sexual_minors: bool,
+    pub sexual_minors: bool,
     pub violence: bool,
     #[serde(rename = "violence/graphic")]
-    pub is_violence_graphic: bool,
+    pub violence_graphic: bool,
 }
 
-#[derive(Debug, Deserialize, Serialize)]
+impl ModerationCategories {
+    /// The wire names (e.g. `self-harm/intent`) of the flagged categories.
+    pub fn flagged_categories(&self) -> Vec<&'static str> {
+        [
+            (self.hate, "hate"),
+            (self.hate_threatening, "hate/threatening"),
+            (self.harassment, "harassment"),
+            (self.harassment_threatening, "harassment/threatening"),
+            (self.self_harm, "self-harm"),
+            (self.self_harm_intent, "self-harm/intent"),
+            (self.self_harm_instructions, "self-harm/instructions"),
+            (self.sexual, "sexual"),
+            (self.sexual_minors, "sexual/minors"),
+            (self.violence, "violence"),
+            (self.violence_graphic, "violence/graphic"),
+        ]
+        .into_iter()
+        .filter(|(flagged, _)| *flagged)
+        .map(|(_, name)| name)
+        .collect()
+    }
+}
+
+/// The model's confidence per category, from 0 to 1.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
 pub struct ModerationCategoryScores {
-    #[serde(rename = "hate")]
-    pub hate_score: f64,
+    pub hate: f64,
     #[serde(rename = "hate/threatening")]
-    pub hate_threatening_score: f64,
+    pub hate_threatening: f64,
+    #[serde(default)]
+    pub harassment: f64,
+    #[serde(default, rename = "harassment/threatening")]
+    pub harassment_threatening: f64,
     #[serde(rename = "self-harm")]
-    pub self_harm_score: f64,
+    pub self_harm: f64,
+    #[serde(default, rename = "self-harm/intent")]
+    pub self_harm_intent: f64,
+    #[serde(default, rename = "self-harm/instructions")]
+    pub self_harm_instructions: f64,
     pub sexual: f64,
     #[serde(rename = "sexual/minors")]
-    pub sexual_minors_score: f64,
+    pub sexual_minors: f64,
     pub violence: f64,
     #[serde(rename = "violence/graphic")]
-    pub violence_graphic_score: f64,
+    pub violence_graphic: f64,
 }
@@ -0,0 +1,277 @@
+//! The unified responses endpoint (beta), which some backends offer in place
+//! of chat completions. A request takes the conversation so far as `input`
+//! items and the response holds the generated `output` items. Only served by
+//! backends that implement `/responses`; the shapes may still change.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use crate::impl_builder_methods;
+use crate::impl_with_headers;
+use crate::v1::chat_completion::MessageRole;
+use crate::v1::common::Usage;
+
+#[derive(Debug, Serialize, Clone)]
+pub struct CreateResponseRequest {
+    pub model: String,
+    pub input: ResponseInput,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub instructions: Option<String>,
+    /// Continues the conversation of a stored response, so `input` only needs
+    /// the new items.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub previous_response_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_output_tokens: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub temperature: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub top_p: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tools: Option<Vec<serde_json::Value>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub store: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<HashMap<String, String>>,
+}
+
+impl CreateResponseRequest {
+    pub fn new(model: String, input: impl Into<ResponseInput>) -> Self {
+        Self {
+            model,
+            input: input.into(),
+            instructions: None,
+            previous_response_id: None,
+            max_output_tokens: None,
+            temperature: None,
+            top_p: None,
+            tools: None,
+            store: None,
+            metadata: None,
+        }
+    }
+}
+
+impl_builder_methods!(
+    CreateResponseRequest,
+    instructions: String,
+    previous_response_id: String,
+    max_output_tokens: i64,
+    temperature: f64,
+    top_p: f64,
+    tools: Vec<serde_json::Value>,
+    store: bool,
+    metadata: HashMap<String, String>
+);
+
+/// A plain prompt, or the prior items of a conversation.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(untagged)]
+pub enum ResponseInput {
+    Text(String),
+    Items(Vec<InputItem>),
+}
+
+impl From<String> for ResponseInput {
+    fn from(text: String) -> Self {
+        ResponseInput::Text(text)
+    }
+}
+
+impl From<&str> for ResponseInput {
+    fn from(text: &str) -> Self {
+        ResponseInput::Text(text.to_owned())
+    }
+}
+
+impl From<Vec<InputItem>> for ResponseInput {
+    fn from(items: Vec<InputItem>) -> Self {
+        ResponseInput::Items(items)
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum InputItem {
+    Message {
+        role: MessageRole,
+        content: String,
+    },
+    /// The result of a `function_call` output item, matched by `call_id`.
+    FunctionCallOutput {
+        call_id: String,
+        output: String,
+    },
+}
+
+impl InputItem {
+    pub fn message(role: MessageRole, content: String) -> Self {
+        InputItem::Message { role, content }
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct ResponseObject {
+    pub id: String,
+    pub object: String,
+    pub created_at: i64,
+    /// `completed`, `in_progress`, `failed`, `incomplete`, ...
+    pub status: String,
+    pub model: String,
+    #[serde(default)]
+    pub output: Vec<OutputItem>,
+    pub usage: Option<Usage>,
+    pub previous_response_id: Option<String>,
+    pub metadata: Option<HashMap<String, String>>,
+    pub headers: Option<HashMap<String, String>>,
+    pub http_status: Option<u16>,
+}
+
+impl_with_headers!(ResponseObject);
+
+impl ResponseObject {
+    /// The text of all `output_text` parts of the output messages, in order.
+    pub fn output_text(&self) -> String {
+        self.output
+            .iter()
+            .filter_map(|item| match item {
+                OutputItem::Message { content, .. } => Some(content),
+                _ => None,
+            })
+            .flatten()
+            .filter_map(|part| match part {
+                OutputContent::OutputText { text, .. } => Some(text.as_str()),
+                _ => None,
+            })
+            .collect()
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum OutputItem {
+    Message {
+        id: String,
+        role: MessageRole,
+        #[serde(default)]
+        content: Vec<OutputContent>,
+        status: Option<String>,
+    },
+    /// A tool call to answer with an `InputItem::FunctionCallOutput`.
+    FunctionCall {
+        id: Option<String>,
+        call_id: String,
+        name: String,
+        arguments: String,
+    },
+    /// An item type this crate doesn't model, such as reasoning or built-in
+    /// tool calls.
+    #[serde(other)]
+    Other,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum OutputContent {
+    OutputText {
+        text: String,
+        #[serde(default)]
+        annotations: Vec<serde_json::Value>,
+    },
+    Refusal {
+        refusal: String,
+    },
+    #[serde(other)]
+    Other,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+pub struct ResponseTextDelta {
+    pub item_id: String,
+    pub output_index: usize,
+    pub content_index: usize,
+    pub delta: String,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+pub struct ResponseFunctionCallArgumentsDelta {
+    pub item_id: String,
+    pub output_index: usize,
+    pub delta: String,
+}
+
+// Lifecycle events wrap the response object.
+#[derive(Deserialize)]
+struct ResponseEnvelope {
+    response: ResponseObject,
+}
+
+#[derive(Deserialize)]
+struct OutputItemEnvelope {
+    output_index: usize,
+    item: OutputItem,
+}
+
+/// One event of a streamed response, named after the SSE `event:` it arrived
+/// as. The `headers` of the carried responses are always `None`.
+#[derive(Debug)]
+pub enum ResponseStreamEvent {
+    Created(ResponseObject),
+    InProgress(ResponseObject),
+    Completed(ResponseObject),
+    Failed(ResponseObject),
+    Incomplete(ResponseObject),
+    OutputItemAdded {
+        output_index: usize,
+        item: OutputItem,
+    },
+    OutputItemDone {
+        output_index: usize,
+        item: OutputItem,
+    },
+    OutputTextDelta(ResponseTextDelta),
+    FunctionCallArgumentsDelta(ResponseFunctionCallArgumentsDelta),
+    /// An event this crate doesn't know yet, with its raw payload.
+    Unknown {
+        event: String,
+        data: serde_json::Value,
+    },
+}
+
+impl ResponseStreamEvent {
+    /// Decodes the `data` of an SSE event named `event`.
+    pub(crate) fn from_sse(event: &str, data: &str) -> Result<Self, serde_json::Error> {
+        use ResponseStreamEvent::*;
+        let response = |data| serde_json::from_str::<ResponseEnvelope>(data).map(|e| e.response);
+        let item = |data| serde_json::from_str::<OutputItemEnvelope>(data);
+        Ok(match event {
+            "response.created" => Created(response(data)?),
+            "response.in_progress" => InProgress(response(data)?),
+            "response.completed" => Completed(response(data)?),
+            "response.failed" => Failed(response(data)?),
+            "response.incomplete" => Incomplete(response(data)?),
+            "response.output_item.added" => {
+                let e = item(data)?;
+                OutputItemAdded {
+                    output_index: e.output_index,
+                    item: e.item,
+                }
+            }
+            "response.output_item.done" => {
+                let e = item(data)?;
+                OutputItemDone {
+                    output_index: e.output_index,
+                    item: e.item,
+                }
+            }
+            "response.output_text.delta" => OutputTextDelta(serde_json::from_str(data)?),
+            "response.function_call_arguments.delta" => {
+                FunctionCallArgumentsDelta(serde_json::from_str(data)?)
+            }
+            _ => Unknown {
+                event: event.to_owned(),
+                data: serde_json::from_str(data)?,
+            },
+        })
+    }
+}
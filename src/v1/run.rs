@@ -1,8 +1,13 @@
-use super::thread::CreateThreadRequest;
+use super::chat_completion::ToolCall;
+use super::message::{MessageDeltaObject, MessageObject};
+use super::thread::{CreateThreadRequest, ThreadObject};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
 use crate::impl_builder_methods;
+use crate::impl_with_headers;
+use crate::v1::common::ListPage;
+use crate::v1::error::{APIError, APIErrorKind};
 
 #[derive(Debug, Serialize, Clone)]
 pub struct CreateRunRequest {
@@ -67,9 +72,9 @@ pub struct RunObject {
     pub created_at: i64,
     pub thread_id: String,
     pub assistant_id: String,
-    pub status: String,
+    pub status: RunStatus,
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub required_action: Option<HashMap<String, String>>,
+    pub required_action: Option<RequiredAction>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub last_error: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -88,6 +93,104 @@ pub struct RunObject {
     pub file_ids: Vec<String>,
     pub metadata: HashMap<String, String>,
     pub headers: Option<HashMap<String, String>>,
+    pub http_status: Option<u16>,
+}
+
+impl_with_headers!(RunObject);
+
+/// Where a run is in its lifecycle. Statuses this crate doesn't know yet are
+/// kept verbatim in `Unknown` instead of failing the whole response.
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq, Hash)]
+#[serde(from = "String", into = "String")]
+pub enum RunStatus {
+    Queued,
+    InProgress,
+    /// Waiting for `Client::submit_tool_outputs`.
+    RequiresAction,
+    Cancelling,
+    Cancelled,
+    Failed,
+    Completed,
+    Expired,
+    Incomplete,
+    Unknown(String),
+}
+
+impl RunStatus {
+    /// Whether the run has stopped for good. `RequiresAction` is not terminal:
+    /// the run continues once tool outputs are submitted. Neither is
+    /// `Unknown`, as nothing says the run has stopped.
+    pub fn is_terminal(&self) -> bool {
+        matches!(
+            self,
+            RunStatus::Cancelled
+                | RunStatus::Failed
+                | RunStatus::Completed
+                | RunStatus::Expired
+                | RunStatus::Incomplete
+        )
+    }
+
+    /// The status as it appears on the wire, e.g. `in_progress`.
+    pub fn as_str(&self) -> &str {
+        match self {
+            RunStatus::Queued => "queued",
+            RunStatus::InProgress => "in_progress",
+            RunStatus::RequiresAction => "requires_action",
+            RunStatus::Cancelling => "cancelling",
+            RunStatus::Cancelled => "cancelled",
+            RunStatus::Failed => "failed",
+            RunStatus::Completed => "completed",
+            RunStatus::Expired => "expired",
+            RunStatus::Incomplete => "incomplete",
+            RunStatus::Unknown(status) => status,
+        }
+    }
+}
+
+impl From<String> for RunStatus {
+    fn from(status: String) -> Self {
+        match status.as_str() {
+            "queued" => RunStatus::Queued,
+            "in_progress" => RunStatus::InProgress,
+            "requires_action" => RunStatus::RequiresAction,
+            "cancelling" => RunStatus::Cancelling,
+            "cancelled" => RunStatus::Cancelled,
+            "failed" => RunStatus::Failed,
+            "completed" => RunStatus::Completed,
+            "expired" => RunStatus::Expired,
+            "incomplete" => RunStatus::Incomplete,
+            _ => RunStatus::Unknown(status),
+        }
+    }
+}
+
+impl From<RunStatus> for String {
+    fn from(status: RunStatus) -> Self {
+        match status {
+            RunStatus::Unknown(status) => status,
+            status => status.as_str().to_owned(),
+        }
+    }
+}
+
+impl std::fmt::Display for RunStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct RequiredAction {
+    #[serde(rename = "type")]
+    pub action_type: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub submit_tool_outputs: Option<SubmitToolOutputs>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct SubmitToolOutputs {
+    pub tool_calls: Vec<ToolCall>,
 }
 
 #[derive(Debug, Deserialize, Serialize)]
@@ -98,6 +201,18 @@ pub struct ListRun {
     pub last_id: String,
     pub has_more: bool,
     pub headers: Option<HashMap<String, String>>,
+    pub http_status: Option<u16>,
+}
+
+impl_with_headers!(ListRun);
+
+impl ListPage for ListRun {
+    type Item = RunObject;
+
+    fn into_page(self) -> (Vec<RunObject>, Option<String>) {
+        let next = self.has_more.then_some(self.last_id);
+        (self.data, next)
+    }
 }
 
 #[derive(Debug, Serialize, Clone)]
@@ -141,8 +256,11 @@ pub struct RunStepObject {
     pub completed_at: Option<i64>,
     pub metadata: HashMap<String, String>,
     pub headers: Option<HashMap<String, String>>,
+    pub http_status: Option<u16>,
 }
 
+impl_with_headers!(RunStepObject);
+
 #[derive(Debug, Deserialize, Serialize, Clone)]
 pub struct ListRunStep {
     pub object: String,
@@ -151,4 +269,206 @@ pub struct ListRunStep {
     pub last_id: String,
     pub has_more: bool,
     pub headers: Option<HashMap<String, String>>,
+    pub http_status: Option<u16>,
+}
+
+impl_with_headers!(ListRunStep);
+
+impl ListPage for ListRunStep {
+    type Item = RunStepObject;
+
+    fn into_page(self) -> (Vec<RunStepObject>, Option<String>) {
+        let next = self.has_more.then_some(self.last_id);
+        (self.data, next)
+    }
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct ToolOutput {
+    pub tool_call_id: String,
+    pub output: String,
+}
+
+impl ToolOutput {
+    pub fn new(tool_call_id: String, output: String) -> Self {
+        Self {
+            tool_call_id,
+            output,
+        }
+    }
+
+    /// Serializes a structured tool result into the JSON string the API expects,
+    /// so callers don't have to encode (and escape) it themselves.
+    pub fn json<T: Serialize>(tool_call_id: String, output: &T) -> Result<Self, APIError> {
+        match serde_json::to_string(output) {
+            Ok(output) => Ok(Self::new(tool_call_id, output)),
+            Err(e) => Err(APIError::new(APIErrorKind::Other, e.to_string())),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct SubmitToolOutputsRequest {
+    pub tool_outputs: Vec<ToolOutput>,
+}
+
+impl SubmitToolOutputsRequest {
+    pub fn new(tool_outputs: Vec<ToolOutput>) -> Self {
+        Self { tool_outputs }
+    }
+}
+
+/// Incremental change to a run step, sent as `thread.run.step.delta`. The
+/// delta's shape depends on the step type, so it is left as JSON.
+#[derive(Debug, Deserialize, Serialize)]
+pub struct RunStepDeltaObject {
+    pub id: String,
+    pub object: String,
+    pub delta: serde_json::Value,
+}
+
+/// One event of a streamed run, named after the SSE `event:` it arrived as.
+/// The `headers` of the carried objects are always `None`.
+#[derive(Debug)]
+pub enum AssistantStreamEvent {
+    ThreadCreated(ThreadObject),
+    RunCreated(RunObject),
+    RunQueued(RunObject),
+    RunInProgress(RunObject),
+    RunRequiresAction(RunObject),
+    RunCompleted(RunObject),
+    RunIncomplete(RunObject),
+    RunFailed(RunObject),
+    RunCancelling(RunObject),
+    RunCancelled(RunObject),
+    RunExpired(RunObject),
+    RunStepCreated(RunStepObject),
+    RunStepInProgress(RunStepObject),
+    RunStepDelta(RunStepDeltaObject),
+    RunStepCompleted(RunStepObject),
+    RunStepFailed(RunStepObject),
+    RunStepCancelled(RunStepObject),
+    RunStepExpired(RunStepObject),
+    MessageCreated(MessageObject),
+    MessageInProgress(MessageObject),
+    MessageDelta(MessageDeltaObject),
+    MessageCompleted(MessageObject),
+    MessageIncomplete(MessageObject),
+    /// An event this crate doesn't know yet, with its raw payload.
+    Unknown {
+        event: String,
+        data: serde_json::Value,
+    },
+}
+
+impl AssistantStreamEvent {
+    /// Decodes the `data` of an SSE event named `event`.
+    pub(crate) fn from_sse(event: &str, data: &str) -> Result<Self, serde_json::Error> {
+        use AssistantStreamEvent::*;
+        Ok(match event {
+            "thread.created" => ThreadCreated(serde_json::from_str(data)?),
+            "thread.run.created" => RunCreated(serde_json::from_str(data)?),
+            "thread.run.queued" => RunQueued(serde_json::from_str(data)?),
+            "thread.run.in_progress" => RunInProgress(serde_json::from_str(data)?),
+            "thread.run.requires_action" => RunRequiresAction(serde_json::from_str(data)?),
+            "thread.run.completed" => RunCompleted(serde_json::from_str(data)?),
+            "thread.run.incomplete" => RunIncomplete(serde_json::from_str(data)?),
+            "thread.run.failed" => RunFailed(serde_json::from_str(data)?),
+            "thread.run.cancelling" => RunCancelling(serde_json::from_str(data)?),
+            "thread.run.cancelled" => RunCancelled(serde_json::from_str(data)?),
+            "thread.run.expired" => RunExpired(serde_json::from_str(data)?),
+            "thread.run.step.created" => RunStepCreated(serde_json::from_str(data)?),
+            "thread.run.step.in_progress" => RunStepInProgress(serde_json::from_str(data)?),
+            "thread.run.step.delta" => RunStepDelta(serde_json::from_str(data)?),
+            "thread.run.step.completed" => RunStepCompleted(serde_json::from_str(data)?),
+            "thread.run.step.failed" => RunStepFailed(serde_json::from_str(data)?),
+            "thread.run.step.cancelled" => RunStepCancelled(serde_json::from_str(data)?),
+            "thread.run.step.expired" => RunStepExpired(serde_json::from_str(data)?),
+            "thread.message.created" => MessageCreated(serde_json::from_str(data)?),
+            "thread.message.in_progress" => MessageInProgress(serde_json::from_str(data)?),
+            "thread.message.delta" => MessageDelta(serde_json::from_str(data)?),
+            "thread.message.completed" => MessageCompleted(serde_json::from_str(data)?),
+            "thread.message.incomplete" => MessageIncomplete(serde_json::from_str(data)?),
+            _ => Unknown {
+                event: event.to_owned(),
+                data: serde_json::from_str(data)?,
+            },
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tool_output_json_encodes_nested_output_once() {
+        let result = serde_json::json!({
+            "price": {"amount": 1000.5, "currency": "USD"},
+            "sources": ["coingecko", "binance"],
+            "note": "quoted \"value\"",
+        });
+        let output = ToolOutput::json("call_1".to_owned(), &result).unwrap();
+        let body = serde_json::to_value(&output).unwrap();
+        let sent = body["output"].as_str().unwrap();
+        assert_eq!(body["tool_call_id"], "call_1");
+        assert_eq!(
+            serde_json::from_str::<serde_json::Value>(sent).unwrap(),
+            result
+        );
+    }
+
+    #[test]
+    fn tool_output_new_sends_raw_output_verbatim() {
+        let output = ToolOutput::new("call_1".to_owned(), "sunny".to_owned());
+        let body = serde_json::to_value(&output).unwrap();
+        assert_eq!(
+            body,
+            serde_json::json!({"tool_call_id": "call_1", "output": "sunny"})
+        );
+    }
+
+    #[test]
+    fn tool_output_json_reports_unserializable_output() {
+        let output = HashMap::from([((1, 2), "non-string key")]);
+        assert!(ToolOutput::json("call_1".to_owned(), &output).is_err());
+    }
+
+    const KNOWN: [(RunStatus, &str, bool); 9] = [
+        (RunStatus::Queued, "queued", false),
+        (RunStatus::InProgress, "in_progress", false),
+        (RunStatus::RequiresAction, "requires_action", false),
+        (RunStatus::Cancelling, "cancelling", false),
+        (RunStatus::Cancelled, "cancelled", true),
+        (RunStatus::Failed, "failed", true),
+        (RunStatus::Completed, "completed", true),
+        (RunStatus::Expired, "expired", true),
+        (RunStatus::Incomplete, "incomplete", true),
+    ];
+
+    #[test]
+    fn known_statuses_round_trip_through_their_wire_names() {
+        for (status, wire, _) in KNOWN {
+            let json = serde_json::to_value(&status).unwrap();
+            assert_eq!(json, wire);
+            assert_eq!(serde_json::from_value::<RunStatus>(json).unwrap(), status);
+            assert_eq!(status.to_string(), wire);
+        }
+    }
+
+    #[test]
+    fn is_terminal_matches_the_lifecycle() {
+        for (status, wire, terminal) in KNOWN {
+            assert_eq!(status.is_terminal(), terminal, "{}", wire);
+        }
+        assert!(!RunStatus::Unknown("paused".to_owned()).is_terminal());
+    }
+
+    #[test]
+    fn unknown_status_is_kept_verbatim() {
+        let status: RunStatus = serde_json::from_str("\"paused\"").unwrap();
+        assert_eq!(status, RunStatus::Unknown("paused".to_owned()));
+        assert_eq!(status.as_str(), "paused");
+        assert_eq!(serde_json::to_string(&status).unwrap(), "\"paused\"");
+    }
 }
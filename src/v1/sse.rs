@@ -0,0 +1,165 @@
+//! Parsing of `text/event-stream` responses, as used by streamed completions
+//! and assistant runs.
+
+use futures::{stream, Stream, TryStreamExt};
+use tokio::io::AsyncBufReadExt;
+use tokio_util::io::StreamReader;
+
+use crate::v1::error::{APIError, APIErrorKind};
+
+/// One event of a server-sent event stream.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ServerSentEvent {
+    /// The `event:` field, `None` for unnamed events.
+    pub event: Option<String>,
+    /// The `data:` lines of the event joined with `\n`.
+    pub data: String,
+    /// The `id:` field, if the event had one.
+    pub id: Option<String>,
+}
+
+// The fields of the event being read, reset by the blank line ending it.
+#[derive(Default)]
+struct PendingEvent {
+    event: Option<String>,
+    data: Option<String>,
+    id: Option<String>,
+}
+
+impl PendingEvent {
+    fn take(&mut self) -> Option<ServerSentEvent> {
+        let pending = std::mem::take(self);
+        Some(ServerSentEvent {
+            event: pending.event,
+            data: pending.data?,
+            id: pending.id,
+        })
+    }
+}
+
+/// Splits `res` into events. Events without any `data:` line, `:` comments
+/// and unknown fields are skipped. A read error is yielded and ends the
+/// stream; so does the end of the body, after flushing an event that wasn't
+/// terminated by a blank line.
+pub fn events(res: reqwest::Response) -> impl Stream<Item = Result<ServerSentEvent, APIError>> {
+    // Obtain a byte stream from the response.
+    let bytes_stream = res.bytes_stream().map_err(std::io::Error::other);
+    //Convert a [Stream] of byte chunks into an [AsyncRead].
+    let reader = StreamReader::new(bytes_stream);
+    // The reader is carried over as-is: it may still buffer further lines of
+    // the current network chunk. `None` marks a stream that already ended.
+    stream::unfold(
+        Some((reader, PendingEvent::default())),
+        |state| async move {
+            let (mut reader, mut pending) = state?;
+            loop {
+                let mut line = String::new();
+                // `read_line` collects raw bytes up to the newline and only then
+                // checks UTF-8, so a multi-byte character split across network
+                // chunks is decoded intact rather than rejected.
+                match reader.read_line(&mut line).await {
+                    Err(e) => {
+                        let err = APIError::new(
+                            APIErrorKind::Stream,
+                            format!("failed to read from response stream: {}", e),
+                        );
+                        return Some((Err(err), None));
+                    }
+                    Ok(0) => return pending.take().map(|event| (Ok(event), None)),
+                    Ok(_) => {}
+                }
+                let line = line.trim_end_matches(['\n', '\r']);
+                if line.is_empty() {
+                    // A blank line dispatches the event.
+                    if let Some(event) = pending.take() {
+                        return Some((Ok(event), Some((reader, pending))));
+                    }
+                    continue;
+                }
+                if line.starts_with(':') {
+                    continue;
+                }
+                let (field, value) = match line.split_once(':') {
+                    Some((field, value)) => (field, value.strip_prefix(' ').unwrap_or(value)),
+                    None => (line, ""),
+                };
+                match field {
+                    "event" => pending.event = Some(value.to_owned()),
+                    "data" => match &mut pending.data {
+                        Some(data) => {
+                            data.push('\n');
+                            data.push_str(value);
+                        }
+                        None => pending.data = Some(value.to_owned()),
+                    },
+                    "id" => pending.id = Some(value.to_owned()),
+                    // `retry` and unknown fields.
+                    _ => {}
+                }
+            }
+        },
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::StreamExt;
+
+    fn response(chunks: Vec<&'static [u8]>) -> reqwest::Response {
+        let chunks = chunks.into_iter().map(Ok::<_, std::io::Error>);
+        let body = reqwest::Body::wrap_stream(stream::iter(chunks));
+        reqwest::Response::from(http::Response::new(body))
+    }
+
+    async fn collect(chunks: Vec<&'static [u8]>) -> Vec<ServerSentEvent> {
+        events(response(chunks))
+            .map(|event| event.unwrap())
+            .collect()
+            .await
+    }
+
+    #[tokio::test]
+    async fn multibyte_character_split_across_chunks() {
+        let text = "data: caf\u{e9} \u{1f600}\n\n".as_bytes();
+        // Split inside the two bytes of `é` and inside the four of the emoji.
+        let e_acute = text.iter().position(|&b| b == 0xc3).unwrap();
+        let emoji = text.iter().position(|&b| b == 0xf0).unwrap();
+        let events = collect(vec![
+            &text[..e_acute + 1],
+            &text[e_acute + 1..emoji + 2],
+            &text[emoji + 2..],
+        ])
+        .await;
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].data, "caf\u{e9} \u{1f600}");
+    }
+
+    #[tokio::test]
+    async fn every_byte_in_its_own_chunk() {
+        let text = "event: delta\ndata: \u{4f60}\u{597d}\ndata: \u{1f44b}\n\n".as_bytes();
+        let events = collect(text.chunks(1).collect()).await;
+        assert_eq!(
+            events,
+            vec![ServerSentEvent {
+                event: Some("delta".to_owned()),
+                data: "\u{4f60}\u{597d}\n\u{1f44b}".to_owned(),
+                id: None,
+            }]
+        );
+    }
+
+    #[tokio::test]
+    async fn unterminated_last_event_is_flushed() {
+        let events = collect(vec![b": comment\n", b"data: a\n\n", b"data: b"]).await;
+        let data: Vec<_> = events.iter().map(|event| event.data.as_str()).collect();
+        assert_eq!(data, ["a", "b"]);
+    }
+
+    #[tokio::test]
+    async fn bare_data_line_is_an_event_with_empty_data() {
+        let events = collect(vec![b"data:\n\ndata: a\n\n"]).await;
+        let data: Vec<_> = events.iter().map(|event| event.data.as_str()).collect();
+        assert_eq!(data, ["", "a"]);
+    }
+}
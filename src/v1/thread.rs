@@ -2,6 +2,7 @@ use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
 use crate::impl_builder_methods;
+use crate::impl_with_headers;
 
 #[derive(Debug, Serialize, Clone)]
 pub struct CreateThreadRequest {
@@ -39,8 +40,11 @@ pub struct ThreadObject {
     pub created_at: i64,
     pub metadata: HashMap<String, String>,
     pub headers: Option<HashMap<String, String>>,
+    pub http_status: Option<u16>,
 }
 
+impl_with_headers!(ThreadObject);
+
 #[derive(Debug, Deserialize, Serialize, Clone)]
 pub struct Message {
     pub role: MessageRole,
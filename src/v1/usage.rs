@@ -0,0 +1,56 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use crate::impl_with_headers;
+
+/// Aggregate usage returned by `Client::usage_summary`, one bucket per day.
+#[derive(Debug, Deserialize, Serialize)]
+pub struct UsageSummary {
+    pub object: String,
+    pub data: Vec<UsageBucket>,
+    #[serde(default)]
+    pub has_more: bool,
+    pub headers: Option<HashMap<String, String>>,
+    pub http_status: Option<u16>,
+}
+
+impl_with_headers!(UsageSummary);
+
+impl UsageSummary {
+    /// Totals per model over every bucket. Results the server didn't
+    /// attribute to a model are keyed by an empty string.
+    pub fn totals_by_model(&self) -> HashMap<String, UsageResult> {
+        let mut totals: HashMap<String, UsageResult> = HashMap::new();
+        for result in self.data.iter().flat_map(|bucket| &bucket.results) {
+            let model = result.model.clone().unwrap_or_default();
+            let total = totals.entry(model.clone()).or_insert_with(|| UsageResult {
+                model: Some(model),
+                ..UsageResult::default()
+            });
+            total.input_tokens += result.input_tokens;
+            total.output_tokens += result.output_tokens;
+            total.num_model_requests += result.num_model_requests;
+        }
+        totals
+    }
+}
+
+/// Usage within `[start_time, end_time)`, both Unix timestamps in seconds.
+#[derive(Debug, Deserialize, Serialize)]
+pub struct UsageBucket {
+    pub start_time: i64,
+    pub end_time: i64,
+    #[serde(default)]
+    pub results: Vec<UsageResult>,
+}
+
+#[derive(Debug, Default, Clone, Deserialize, Serialize)]
+pub struct UsageResult {
+    pub model: Option<String>,
+    #[serde(default, alias = "prompt_tokens")]
+    pub input_tokens: i64,
+    #[serde(default, alias = "completion_tokens")]
+    pub output_tokens: i64,
+    #[serde(default)]
+    pub num_model_requests: i64,
+}